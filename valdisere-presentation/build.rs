@@ -0,0 +1,321 @@
+//! De-identifies the study exports before they are compiled into the deck.
+//!
+//! The raw data in `private/` carries hospital MRNs and real dates. It is
+//! read from the study workbook, `private/standup_results.ods`, one sheet per
+//! export; without the workbook, from per-sheet CSVs of the same names. Each MRN
+//! is replaced by its study ID from `private/study_ids.csv`, and every
+//! `RECORDED_TIME` and `PROCEDURE_DATE` is moved by that patient's date
+//! shift, so intervals within a patient are unchanged. The results are
+//! written to `OUT_DIR`, where the data modules `include_str!` them.
+//! Procedure dates are optional; without them that export is written with
+//! its header only.
+//!
+//! A clone without `private/` builds with every export written empty, so the
+//! deck, its tests and the tools still run, on no data. Once the directory
+//! exists its mapping is required as before.
+//!
+//! Everything that can reach the binary is then scanned for MRN-shaped
+//! strings: the sources, `assets/` and the de-identified files. A match fails
+//! the build, so an MRN cannot end up in the WASM output.
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+// Shared with the app, which reads workbooks the same way at runtime.
+#[allow(dead_code)]
+#[path = "src/data/workbook.rs"]
+mod workbook;
+
+use workbook::Workbook;
+
+#[path = "src/data/mrn.rs"]
+mod mrn;
+
+use mrn::{first_mrn, is_mrn};
+
+const PRIVATE_DIR: &str = "private";
+const WORKBOOK: &str = "private/standup_results.ods";
+const MAPPING: &str = "private/study_ids.csv";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Directories whose contents are compiled in, checked for leaks.
+const EMBEDDED_DIRS: &[&str] = &["src", "assets"];
+
+/// An export: the file written to `OUT_DIR`, the workbook sheet it comes
+/// from with the columns that identify that sheet, and the columns that
+/// identify a patient.
+struct Export {
+    file: &'static str,
+    sheet: &'static str,
+    columns: &'static [&'static str],
+    id_column: &'static str,
+    time_column: Option<&'static str>,
+    /// A missing sheet or file is not an error.
+    optional: bool,
+}
+
+const EXPORTS: &[Export] = &[
+    Export {
+        file: "standup_results.csv",
+        sheet: workbook::SHEET_BP,
+        columns: &["PAT_MRN_ID", "RECORDED_TIME", "BP_POSITION"],
+        id_column: "PAT_MRN_ID",
+        time_column: Some("RECORDED_TIME"),
+        optional: false,
+    },
+    Export {
+        file: "standup_results_meq.csv",
+        sheet: workbook::SHEET_MEQ,
+        columns: &["PAT_MRN_ID", "MEQ_Pre", "MEQ_Post"],
+        id_column: "PAT_MRN_ID",
+        time_column: None,
+        optional: false,
+    },
+    Export {
+        file: "standup_results_cgi.csv",
+        sheet: workbook::SHEET_CGI,
+        columns: &["PAT_MRN_ID", "CGI-I Score"],
+        id_column: "PAT_MRN_ID",
+        time_column: None,
+        optional: false,
+    },
+    Export {
+        file: "standup_results_bp.csv",
+        sheet: workbook::SHEET_SUMMARY,
+        columns: &["Patient ID"],
+        id_column: "Patient ID",
+        time_column: None,
+        optional: false,
+    },
+    Export {
+        file: "standup_results_procedures.csv",
+        sheet: workbook::SHEET_PROCEDURES,
+        columns: &["PAT_MRN_ID", "PROCEDURE_DATE"],
+        id_column: "PAT_MRN_ID",
+        time_column: Some("PROCEDURE_DATE"),
+        optional: true,
+    },
+];
+
+/// The per-patient summary read by the deprecated Standup1 slide.
+const JSON_EXPORT: &str = "standup_data.json";
+
+struct StudyPatient {
+    id: String,
+    shift: TimeDelta,
+}
+
+fn load_mapping() -> BTreeMap<String, StudyPatient> {
+    let mut reader = csv::Reader::from_path(MAPPING)
+        .unwrap_or_else(|e| panic!("{MAPPING}: {e}; the study ID mapping is required"));
+    let mut mapping = BTreeMap::new();
+    for (i, row) in reader.records().enumerate() {
+        let row = row.unwrap_or_else(|e| panic!("{MAPPING}: {e}"));
+        let (Some(mrn), Some(id), Some(shift)) = (row.get(0), row.get(1), row.get(2)) else {
+            panic!(
+                "{MAPPING}: row {} needs PAT_MRN_ID, STUDY_ID and DATE_SHIFT_DAYS",
+                i + 2
+            );
+        };
+        let days: i64 = shift
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{MAPPING}: '{shift}' is not a whole number of days"));
+        assert!(!is_mrn(id), "{MAPPING}: study ID '{id}' looks like an MRN");
+        mapping.insert(
+            mrn.trim().to_string(),
+            StudyPatient {
+                id: id.trim().to_string(),
+                shift: TimeDelta::days(days),
+            },
+        );
+    }
+    mapping
+}
+
+/// The study patient for an ID cell. Cells that are not MRNs, such as the
+/// summary rows at the foot of an export, are left alone.
+fn study_patient<'a>(
+    mapping: &'a BTreeMap<String, StudyPatient>,
+    file: &str,
+    value: &str,
+) -> Option<&'a StudyPatient> {
+    let value = value.trim();
+    if !is_mrn(value) {
+        return None;
+    }
+    let patient = mapping.get(value);
+    assert!(
+        patient.is_some(),
+        "{file}: an MRN has no study ID; add it to {MAPPING}"
+    );
+    patient
+}
+
+/// The raw export as CSV text, with a name for error messages. An optional
+/// export that is missing comes back as its header row alone.
+fn raw_export(export: &Export, workbook: Option<&mut Workbook>) -> (String, String) {
+    let (source, text) = match workbook {
+        Some(workbook) => (
+            format!("{WORKBOOK} [{}]", export.sheet),
+            workbook
+                .sheet_csv(export.sheet, export.columns)
+                .map_err(|e| e.to_string()),
+        ),
+        None => {
+            let path = Path::new(PRIVATE_DIR).join(export.file);
+            (
+                path.display().to_string(),
+                fs::read_to_string(&path).map_err(|e| e.to_string()),
+            )
+        }
+    };
+    match text {
+        Ok(text) => (source, text),
+        Err(_) if export.optional => (source, export.columns.join(",") + "\n"),
+        Err(e) => panic!("{source}: {e}"),
+    }
+}
+
+/// A time or date shifted by `shift`, in the format it came in.
+fn shift_time(field: &str, shift: TimeDelta) -> Option<String> {
+    if let Ok(time) = NaiveDateTime::parse_from_str(field, TIME_FORMAT) {
+        return Some((time + shift).format(TIME_FORMAT).to_string());
+    }
+    let date = NaiveDate::parse_from_str(field, DATE_FORMAT).ok()?;
+    Some((date + shift).format(DATE_FORMAT).to_string())
+}
+
+fn deidentify_csv(
+    export: &Export,
+    workbook: Option<&mut Workbook>,
+    mapping: &BTreeMap<String, StudyPatient>,
+    out: &Path,
+) {
+    let (source, text) = raw_export(export, workbook);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = reader
+        .headers()
+        .unwrap_or_else(|e| panic!("{source}: {e}"))
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+    let id_column = column(export.id_column)
+        .unwrap_or_else(|| panic!("{}: no '{}' column", export.file, export.id_column));
+    let time_column = export.time_column.and_then(column);
+
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_path(out.join(export.file))
+        .unwrap_or_else(|e| panic!("{}: {e}", export.file));
+    writer.write_record(&headers).expect("write header");
+    for row in reader.records() {
+        let row = row.unwrap_or_else(|e| panic!("{source}: {e}"));
+        let patient = study_patient(mapping, export.file, row.get(id_column).unwrap_or(""));
+        let fields = row.iter().enumerate().map(|(i, field)| {
+            if i == id_column {
+                return patient.map_or(field.to_string(), |p| p.id.clone());
+            }
+            if Some(i) == time_column {
+                // A time that cannot be shifted is dropped rather than kept
+                // real; the loader then rejects the row as it would have.
+                return patient
+                    .and_then(|p| shift_time(field.trim(), p.shift))
+                    .unwrap_or_default();
+            }
+            field.to_string()
+        });
+        writer
+            .write_record(fields.collect::<Vec<_>>())
+            .expect("write row");
+    }
+    writer.flush().expect("flush de-identified export");
+}
+
+fn deidentify_json(mapping: &BTreeMap<String, StudyPatient>, out: &Path) {
+    let source = Path::new(PRIVATE_DIR).join(JSON_EXPORT);
+    let text = fs::read_to_string(&source).unwrap_or_else(|e| panic!("{}: {e}", source.display()));
+    let mut patients: serde_json::Value =
+        serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", source.display()));
+    for patient in patients.as_array_mut().into_iter().flatten() {
+        if let Some(mrn) = patient.get_mut("mrn") {
+            let id = mrn
+                .as_str()
+                .and_then(|m| study_patient(mapping, JSON_EXPORT, m))
+                .map(|p| p.id.clone());
+            if let Some(id) = id {
+                *mrn = serde_json::Value::String(id);
+            }
+        }
+    }
+    let json = serde_json::to_string_pretty(&patients).expect("serialise");
+    fs::write(out.join(JSON_EXPORT), json).expect("write de-identified export");
+}
+
+fn files_under(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files_under(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Fails the build if anything compiled in contains an MRN-shaped string.
+/// Only the location is reported, so the build log does not leak it either.
+fn check_for_mrns(out: &Path) {
+    let mut files = Vec::new();
+    for dir in EMBEDDED_DIRS {
+        files_under(Path::new(dir), &mut files);
+    }
+    files.extend(EXPORTS.iter().map(|e| out.join(e.file)));
+    files.push(out.join(JSON_EXPORT));
+
+    let mut leaks = Vec::new();
+    for file in files {
+        let Ok(bytes) = fs::read(&file) else { continue };
+        if let Some(offset) = first_mrn(&bytes) {
+            leaks.push(format!("{} (byte {offset})", file.display()));
+        }
+    }
+    assert!(
+        leaks.is_empty(),
+        "MRN-shaped strings would be embedded in the build:\n  {}",
+        leaks.join("\n  ")
+    );
+}
+
+fn main() {
+    println!("cargo::rerun-if-changed={PRIVATE_DIR}");
+    for dir in EMBEDDED_DIRS {
+        println!("cargo::rerun-if-changed={dir}");
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    if !Path::new(PRIVATE_DIR).is_dir() {
+        println!("cargo::warning=no {PRIVATE_DIR}/ directory, building without study data");
+        for export in EXPORTS {
+            fs::write(out.join(export.file), "").expect("write empty export");
+        }
+        fs::write(out.join(JSON_EXPORT), "[]").expect("write empty export");
+        check_for_mrns(&out);
+        return;
+    }
+    let mapping = load_mapping();
+    let mut workbook = fs::read(WORKBOOK)
+        .ok()
+        .map(|bytes| Workbook::open(bytes).unwrap_or_else(|e| panic!("{WORKBOOK}: {e}")));
+    for export in EXPORTS {
+        deidentify_csv(export, workbook.as_mut(), &mapping, &out);
+    }
+    deidentify_json(&mapping, &out);
+    check_for_mrns(&out);
+}
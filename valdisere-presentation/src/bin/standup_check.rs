@@ -0,0 +1,141 @@
+//! Checks the per-patient summary sheet against the raw BP readings.
+//!
+//! Usage: `cargo run --bin standup-check -- [--tolerance MMHG] [input...]`
+//!
+//! The input is the study workbook (ODS or XLSX), read from its `BP` and
+//! `BP summary` sheets, or the two CSV exports, readings first. Without an
+//! input the de-identified data compiled into the deck is checked.
+//!
+//! Each summary value further than the tolerance from the recomputed one is
+//! listed, as is every patient found in only one of the two. The exit status
+//! is 1 if anything was listed, so the check can gate a data update.
+
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+use valdisere_presentation::data::consistency::{check, ConsistencyReport, DEFAULT_TOLERANCE};
+use valdisere_presentation::data::datasets::Datasets;
+use valdisere_presentation::data::ingest::{load_csv, CsvRecord, LoadReport};
+use valdisere_presentation::data::records::{BpRecord, SummaryRecord};
+use valdisere_presentation::data::workbook::{is_workbook, Workbook};
+
+const USAGE: &str =
+    "usage: standup-check [--tolerance MMHG] [workbook.ods | readings.csv summary.csv]";
+
+fn load_file<T: CsvRecord>(path: &str) -> Result<LoadReport<T>, Box<dyn Error>> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    Ok(load_csv(&content)?)
+}
+
+/// The readings and the summary, with the name of each source.
+type Inputs = (
+    (String, LoadReport<BpRecord>),
+    (String, LoadReport<SummaryRecord>),
+);
+
+fn load(inputs: &[String]) -> Result<Inputs, Box<dyn Error>> {
+    match inputs {
+        [] => {
+            let data = Datasets::bundled();
+            Ok((
+                ("bundled readings".to_string(), data.bp),
+                ("bundled summary".to_string(), data.summary),
+            ))
+        }
+        [workbook] if is_workbook(workbook) => {
+            let bytes = fs::read(workbook).map_err(|e| format!("{workbook}: {e}"))?;
+            let mut book = Workbook::open(bytes).map_err(|e| format!("{workbook}: {e}"))?;
+            let data = Datasets::from_workbook(&mut book);
+            if let Some(error) = data.bp.error.as_ref().or(data.summary.error.as_ref()) {
+                return Err(format!("{workbook}: {error}").into());
+            }
+            Ok((
+                (workbook.clone(), data.bp),
+                (workbook.clone(), data.summary),
+            ))
+        }
+        [readings, summary] => Ok((
+            (readings.clone(), load_file(readings)?),
+            (summary.clone(), load_file(summary)?),
+        )),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn value(v: Option<f64>) -> String {
+    v.map_or_else(|| "blank".to_string(), |v| format!("{v:.2}"))
+}
+
+fn print(report: &ConsistencyReport) {
+    for d in &report.discrepancies {
+        let patient = d
+            .number
+            .map_or_else(|| d.mrn.clone(), |n| format!("patient {n} ({})", d.mrn));
+        let difference = d
+            .difference()
+            .map(|x| format!(" ({x:+.2})"))
+            .unwrap_or_default();
+        println!(
+            "{patient}: {} is {} in the summary, {} from the readings{difference}",
+            d.measure,
+            value(d.summary),
+            value(d.recomputed),
+        );
+    }
+    for mrn in &report.only_in_summary {
+        println!("{mrn}: in the summary but has no readings");
+    }
+    for mrn in &report.only_in_readings {
+        println!("{mrn}: has readings but is missing from the summary");
+    }
+    println!(
+        "{} patients checked, {} differ by more than {} mmHg, {} in one file only",
+        report.checked,
+        report.patients_differing(),
+        report.tolerance,
+        report.only_in_summary.len() + report.only_in_readings.len(),
+    );
+}
+
+fn run(args: &[String]) -> Result<bool, Box<dyn Error>> {
+    let mut tolerance = DEFAULT_TOLERANCE;
+    let mut inputs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--tolerance" {
+            let raw = args.next().ok_or(USAGE)?;
+            tolerance = raw
+                .parse()
+                .map_err(|_| format!("--tolerance: '{raw}' is not a number"))?;
+        } else {
+            inputs.push(arg.clone());
+        }
+    }
+    let ((bp_source, bp), (summary_source, summary)) = load(&inputs)?;
+    for (source, rejected) in [
+        (&bp_source, &bp.rejected),
+        (&summary_source, &summary.rejected),
+    ] {
+        for row in rejected {
+            eprintln!("{source}:{}: skipped, {}", row.line, row.reason);
+        }
+    }
+    for value in &bp.cleared {
+        eprintln!("{bp_source}:{}: {}", value.line, value.reason);
+    }
+    let report = check(&bp.records, &summary.records, tolerance);
+    print(&report);
+    Ok(report.is_consistent())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("standup-check: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Builds `standup_data.json` from the blood pressure data, replacing the
+//! old `process_data.py`.
+//!
+//! Usage: `cargo run --bin standup-data -- [--rule RULE] [--missing PATH]
+//! <input> [output.json]`
+//!
+//! The input is the study workbook (ODS or XLSX), read from its BP sheet, or
+//! a CSV export of that sheet. `RULE` is how each patient's visits become one
+//! value per phase and position: `pooled` (the default, the mean of every
+//! reading, as the file has always held), `mean`, `first`, `worst` or
+//! `last`; see `data::visits`.
+//!
+//! Rows are read with the app's own loader, so a row it would reject is
+//! skipped here too and reported on stderr.
+//!
+//! How many patients each analysis has complete data for is reported on
+//! stderr as well; `--missing` writes the patients with gaps, and what they
+//! lack, to a CSV. From a workbook that covers every analysis on the Results
+//! slide, from a CSV export only the hemodynamic ones; see `data::missing`.
+//!
+//! The JSON goes to stdout unless an output path is given. The export's
+//! MRNs are kept, so write it to `private/standup_data.json`, where the
+//! build de-identifies it along with the other exports.
+
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+use valdisere_presentation::data::datasets::Datasets;
+use valdisere_presentation::data::ingest::{load_csv, LoadReport};
+use valdisere_presentation::data::missing::{bp_missingness, Missingness};
+use valdisere_presentation::data::positional::aggregate;
+use valdisere_presentation::data::records::BpRecord;
+use valdisere_presentation::data::visits::Aggregation;
+use valdisere_presentation::data::workbook::{is_workbook, Workbook};
+
+const USAGE: &str = "usage: standup-data [--rule pooled|mean|first|worst|last] [--missing missing.csv] <workbook.ods|export.csv> [output.json]";
+
+/// The readings, and what each analysis is missing.
+fn load(input: &str) -> Result<(LoadReport<BpRecord>, Vec<Missingness>), Box<dyn Error>> {
+    if is_workbook(input) {
+        let bytes = fs::read(input).map_err(|e| format!("{input}: {e}"))?;
+        let mut workbook = Workbook::open(bytes).map_err(|e| format!("{input}: {e}"))?;
+        let mut data = Datasets::from_workbook(&mut workbook);
+        if let Some(e) = data.bp.error.take() {
+            return Err(e.into());
+        }
+        let missing = data.missingness();
+        return Ok((data.bp, missing));
+    }
+    let content = fs::read_to_string(input).map_err(|e| format!("{input}: {e}"))?;
+    let report = load_csv(&content)?;
+    let missing = bp_missingness(&report);
+    Ok((report, missing))
+}
+
+fn write_missing(path: &str, analyses: &[Missingness]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path).map_err(|e| format!("{path}: {e}"))?;
+    writer.write_record(["Analysis", "PAT_MRN_ID", "Missing"])?;
+    for a in analyses {
+        for entry in &a.missing {
+            writer.write_record([a.analysis, &entry.mrn, &entry.what])?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn run(
+    input: &str,
+    output: Option<&str>,
+    rule: Aggregation,
+    missing_path: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let (report, missing) = load(input)?;
+    for row in &report.rejected {
+        eprintln!("{input}:{}: skipped, {}", row.line, row.reason);
+    }
+    for value in &report.cleared {
+        eprintln!("{input}:{}: {}", value.line, value.reason);
+    }
+    let patients = aggregate(&report.records, rule);
+    let json = serde_json::to_string_pretty(&patients)?;
+    match output {
+        Some(path) => fs::write(path, json + "\n").map_err(|e| format!("{path}: {e}"))?,
+        None => println!("{json}"),
+    }
+    eprintln!(
+        "{} patients from {} readings, {}",
+        patients.len(),
+        report.records.len(),
+        rule.describe()
+    );
+    for a in &missing {
+        eprintln!(
+            "{}: {} of {} {} complete",
+            a.analysis,
+            a.complete(),
+            a.patients,
+            a.base
+        );
+    }
+    if let Some(path) = missing_path {
+        write_missing(path, &missing)?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut rule = Aggregation::Pooled;
+    if let Some(i) = args.iter().position(|a| a == "--rule") {
+        let Some(parsed) = args.get(i + 1).and_then(|name| Aggregation::parse(name)) else {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        };
+        rule = parsed;
+        args.drain(i..=i + 1);
+    }
+    let mut missing = None;
+    if let Some(i) = args.iter().position(|a| a == "--missing") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        };
+        missing = Some(path.clone());
+        args.drain(i..=i + 1);
+    }
+    let (input, output) = match args.as_slice() {
+        [input] => (input.as_str(), None),
+        [input, output] => (input.as_str(), Some(output.as_str())),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(input, output, rule, missing.as_deref()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("standup-data: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
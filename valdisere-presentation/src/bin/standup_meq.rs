@@ -0,0 +1,160 @@
+//! Computes each patient's midodrine-equivalent dose (MEQ) from their
+//! prescriptions, and checks a stored MEQ export against it.
+//!
+//! Usage: `cargo run --bin standup-meq -- [--table EQUIVALENTS.csv]
+//! [--tolerance ME] <prescriptions> [meq.csv]`
+//!
+//! The prescriptions are the study workbook (ODS or XLSX), read from its
+//! `Prescriptions` sheet, or a CSV with the columns `PAT_MRN_ID`,
+//! `pre or post procedure`, `Drug`, `Dose_mg` and `Doses_per_day`. The
+//! equivalence table comes from `--table`, else the workbook's
+//! `MEQ equivalents` sheet, else the defaults in `data::medication`.
+//!
+//! The computed MEQ goes to stdout in the columns of the MEQ export. The
+//! stored MEQ, from `meq.csv` or else the workbook's `MEQ` sheet, is then
+//! compared patient by patient; any disagreement is listed on stderr and the
+//! exit status is 1.
+
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+use valdisere_presentation::data::datasets::Datasets;
+use valdisere_presentation::data::ingest::{load_csv, CsvRecord, LoadReport};
+use valdisere_presentation::data::medication::{
+    check_meq, compute_meq, EquivalenceTable, MeqCheck, DEFAULT_MEQ_TOLERANCE,
+};
+use valdisere_presentation::data::records::{
+    Drug, EquivalenceRecord, MeqRecord, Phase, PrescriptionRecord,
+};
+use valdisere_presentation::data::workbook::{is_workbook, Workbook};
+
+const USAGE: &str = "usage: standup-meq [--table equivalents.csv] [--tolerance ME] <workbook.ods | prescriptions.csv> [meq.csv]";
+
+fn load_file<T: CsvRecord>(path: &str) -> Result<LoadReport<T>, Box<dyn Error>> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    Ok(load_csv(&content)?)
+}
+
+fn skipped<T>(source: &str, report: &LoadReport<T>) {
+    for row in &report.rejected {
+        eprintln!("{source}:{}: skipped, {}", row.line, row.reason);
+    }
+    for value in &report.cleared {
+        eprintln!("{source}:{}: {}", value.line, value.reason);
+    }
+}
+
+fn print(report: &MeqCheck) {
+    for d in &report.discrepancies {
+        let phase = if d.phase == Phase::Pre { "pre" } else { "post" };
+        eprintln!(
+            "{}: {phase} MEQ is {:.2} ME stored, {:.2} ME from the prescriptions ({:+.2})",
+            d.mrn,
+            d.stored,
+            d.computed,
+            d.difference()
+        );
+    }
+    for mrn in &report.only_stored {
+        eprintln!("{mrn}: has a stored MEQ but no prescriptions");
+    }
+    for mrn in &report.only_prescribed {
+        eprintln!("{mrn}: has prescriptions but no stored MEQ");
+    }
+    eprintln!(
+        "{} patients checked, {} differ by more than {} ME, {} in one file only",
+        report.checked,
+        report.patients_differing(),
+        report.tolerance,
+        report.only_stored.len() + report.only_prescribed.len(),
+    );
+}
+
+fn run(args: &[String]) -> Result<bool, Box<dyn Error>> {
+    let mut table_path = None;
+    let mut tolerance = DEFAULT_MEQ_TOLERANCE;
+    let mut inputs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--table" => table_path = Some(args.next().ok_or(USAGE)?.clone()),
+            "--tolerance" => {
+                let raw = args.next().ok_or(USAGE)?;
+                tolerance = raw
+                    .parse()
+                    .map_err(|_| format!("--tolerance: '{raw}' is not a number"))?;
+            }
+            _ => inputs.push(arg.clone()),
+        }
+    }
+
+    let (prescriptions, mut equivalents, mut stored): (
+        LoadReport<PrescriptionRecord>,
+        LoadReport<EquivalenceRecord>,
+        Option<LoadReport<MeqRecord>>,
+    ) = match inputs.as_slice() {
+        [workbook, ..] if is_workbook(workbook) => {
+            let bytes = fs::read(workbook).map_err(|e| format!("{workbook}: {e}"))?;
+            let mut book = Workbook::open(bytes).map_err(|e| format!("{workbook}: {e}"))?;
+            let data = Datasets::from_workbook(&mut book);
+            if data.prescriptions.records.is_empty() {
+                return Err(format!("{workbook}: no prescriptions found").into());
+            }
+            skipped(workbook, &data.prescriptions);
+            let stored = data.meq.error.is_none().then_some(data.meq);
+            (data.prescriptions, data.equivalents, stored)
+        }
+        [prescriptions, ..] => {
+            let report = load_file(prescriptions)?;
+            skipped(prescriptions, &report);
+            (report, LoadReport::empty(), None)
+        }
+        [] => return Err(USAGE.into()),
+    };
+    match inputs.as_slice() {
+        [_] => {}
+        [_, meq] => {
+            let report = load_file(meq)?;
+            skipped(meq, &report);
+            stored = Some(report);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    if let Some(path) = &table_path {
+        equivalents = load_file(path)?;
+        skipped(path, &equivalents);
+    }
+
+    let table = EquivalenceTable::with(&equivalents.records);
+    for drug in Drug::ALL {
+        eprintln!("{}: {} ME per mg", drug.label(), table.factor(drug));
+    }
+    let computed = compute_meq(&prescriptions.records, &table);
+    println!("PAT_MRN_ID,MEQ_Pre,MEQ_Post,Percent_Reduction");
+    for r in &computed {
+        let reduction = r
+            .percent_reduction()
+            .map(|p| format!("{p:.1}"))
+            .unwrap_or_default();
+        println!("{},{:.2},{:.2},{reduction}", r.mrn, r.pre, r.post);
+    }
+
+    let Some(stored) = stored else {
+        return Ok(true);
+    };
+    let report = check_meq(&stored.records, &computed, tolerance);
+    print(&report);
+    Ok(report.is_consistent())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("standup-meq: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Scans what `dx` built for MRN-shaped strings.
+//!
+//! Usage: `cargo run --bin standup-scan -- [dir...]`
+//!
+//! `build.rs` checks the sources and the de-identified data, but not the
+//! bundle `dx build` or `dx bundle` writes, which is what gets deployed.
+//! This reads every file under the given directories (by default
+//! `target/dx`) and lists each one containing an MRN, by byte offset only so
+//! the output does not leak it. The exit status is 1 if anything was listed.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use valdisere_presentation::data::mrn::first_mrn;
+
+const DEFAULT_DIR: &str = "target/dx";
+
+fn files_under(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    for entry in fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files_under(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<bool, Box<dyn Error>> {
+    let dirs = if args.is_empty() {
+        vec![DEFAULT_DIR.to_string()]
+    } else {
+        args.to_vec()
+    };
+    let mut files = Vec::new();
+    for dir in &dirs {
+        files_under(Path::new(dir), &mut files)?;
+    }
+    let mut leaks = 0;
+    for file in &files {
+        let bytes = fs::read(file).map_err(|e| format!("{}: {e}", file.display()))?;
+        if let Some(offset) = first_mrn(&bytes) {
+            println!("{}: MRN-shaped string at byte {offset}", file.display());
+            leaks += 1;
+        }
+    }
+    println!(
+        "{} files scanned under {}, {leaks} with an MRN-shaped string",
+        files.len(),
+        dirs.join(", ")
+    );
+    Ok(leaks == 0)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("standup-scan: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
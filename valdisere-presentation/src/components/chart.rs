@@ -0,0 +1,1012 @@
+//! SVG charts shared by every slide.
+//!
+//! Charts are drawn in a fixed viewBox and scale with their container. The
+//! value axis always includes zero and is extended to "nice" tick values, so
+//! no chart needs a hand-picked scale.
+
+use dioxus::prelude::*;
+
+/// Colours come from the Tailwind theme in `input.css`, so charts follow the
+/// brand palette without duplicating hex values.
+pub mod theme {
+    pub const ORANGE: &str = "var(--color-brand-orange)";
+    pub const LIGHT: &str = "var(--color-brand-light)";
+    pub const TAUPE: &str = "var(--color-brand-taupe)";
+    pub const GREEN: &str = "var(--color-brand-green)";
+    pub const DARK: &str = "var(--color-brand-dark)";
+
+    /// Before the procedure.
+    pub const PRE: &str = ORANGE;
+    /// After the procedure.
+    pub const POST: &str = LIGHT;
+
+    pub const AXIS: &str = TAUPE;
+
+    pub const IMPROVED: &str = LIGHT;
+    pub const WORSENED: &str = ORANGE;
+}
+
+const WIDTH: f64 = 600.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 52.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 28.0;
+const MARGIN_BOTTOM: f64 = 44.0;
+const TARGET_TICKS: usize = 5;
+/// Share of each category's band left empty between groups.
+const BAND_PADDING: f64 = 0.25;
+
+/// A step of 1, 2 or 5 times a power of ten, at least `raw`.
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Evenly spaced round tick values covering `[min, max]`, about `target` of
+/// them. The first and last ticks bound the axis.
+pub fn nice_ticks(min: f64, max: f64, target: usize) -> Vec<f64> {
+    let (min, max) = if max > min {
+        (min, max)
+    } else {
+        (min, min + 1.0)
+    };
+    let step = nice_step((max - min) / target.saturating_sub(1).max(1) as f64);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    // Multiplying integer indices avoids accumulating rounding error.
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Formats a value with `precision` decimals, dropping "-0".
+pub fn format_value(value: f64, precision: usize) -> String {
+    let text = format!("{value:.precision$}");
+    if text
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub name: String,
+    /// One value per category; `None` where there is no data, drawn as
+    /// "n/a" rather than as a bar of zero.
+    pub values: Vec<Option<f64>>,
+    pub color: &'static str,
+}
+
+impl Series {
+    pub fn new(name: impl Into<String>, values: Vec<f64>, color: &'static str) -> Self {
+        Self::partial(name, values.into_iter().map(Some).collect(), color)
+    }
+
+    /// A series with gaps.
+    pub fn partial(name: impl Into<String>, values: Vec<Option<f64>>, color: &'static str) -> Self {
+        Self {
+            name: name.into(),
+            values,
+            color,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BarLayout {
+    /// Series side by side within each category.
+    #[default]
+    Grouped,
+    /// Series on top of each other, with the category total above.
+    Stacked,
+}
+
+/// Maps values onto the vertical pixel axis of the plot area.
+#[derive(Clone, Copy)]
+struct Scale {
+    min: f64,
+    max: f64,
+}
+
+impl Scale {
+    fn from_ticks(ticks: &[f64]) -> Self {
+        Self {
+            min: ticks.first().copied().unwrap_or(0.0),
+            max: ticks.last().copied().unwrap_or(1.0),
+        }
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let plot = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        MARGIN_TOP + plot * (self.max - value) / (self.max - self.min)
+    }
+}
+
+/// One drawn rectangle and its label.
+struct Bar {
+    x: f64,
+    width: f64,
+    from: f64,
+    to: f64,
+    color: &'static str,
+    label: Option<String>,
+}
+
+#[component]
+pub fn BarChart(
+    title: String,
+    categories: Vec<String>,
+    series: Vec<Series>,
+    #[props(default)] layout: BarLayout,
+    /// Shown above the value axis, e.g. "mmHg".
+    #[props(default)]
+    unit: String,
+    /// Decimals in value labels.
+    #[props(default)]
+    precision: usize,
+) -> Element {
+    let value = |s: &Series, c: usize| s.values.get(c).copied().flatten();
+
+    // Value range, including zero and, when stacked, the stacked extremes.
+    let (mut low, mut high) = (0.0_f64, 0.0_f64);
+    for c in 0..categories.len() {
+        match layout {
+            BarLayout::Grouped => {
+                for v in series.iter().filter_map(|s| value(s, c)) {
+                    low = low.min(v);
+                    high = high.max(v);
+                }
+            }
+            BarLayout::Stacked => {
+                let up: f64 = series
+                    .iter()
+                    .filter_map(|s| value(s, c))
+                    .map(|v| v.max(0.0))
+                    .sum();
+                let down: f64 = series
+                    .iter()
+                    .filter_map(|s| value(s, c))
+                    .map(|v| v.min(0.0))
+                    .sum();
+                low = low.min(down);
+                high = high.max(up);
+            }
+        }
+    }
+    let ticks = nice_ticks(low, high, TARGET_TICKS);
+    let scale = Scale::from_ticks(&ticks);
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let band = plot_width / categories.len().max(1) as f64;
+    let group_width = band * (1.0 - BAND_PADDING);
+
+    let mut bars: Vec<Bar> = Vec::new();
+    let mut totals: Vec<(f64, f64, String)> = Vec::new();
+    // Where a value is missing: the centre of its slot.
+    let mut gaps: Vec<f64> = Vec::new();
+    for c in 0..categories.len() {
+        let left = MARGIN_LEFT + band * c as f64 + (band - group_width) / 2.0;
+        match layout {
+            BarLayout::Grouped => {
+                let width = group_width / series.len().max(1) as f64;
+                for (i, s) in series.iter().enumerate() {
+                    let Some(v) = value(s, c) else {
+                        gaps.push(left + width * (i as f64 + 0.5));
+                        continue;
+                    };
+                    bars.push(Bar {
+                        x: left + width * i as f64 + 1.0,
+                        width: (width - 2.0).max(1.0),
+                        from: 0.0,
+                        to: v,
+                        color: s.color,
+                        label: Some(format_value(v, precision)),
+                    });
+                }
+            }
+            BarLayout::Stacked => {
+                let (mut up, mut down) = (0.0, 0.0);
+                let values: Vec<f64> = series.iter().filter_map(|s| value(s, c)).collect();
+                if values.is_empty() {
+                    gaps.push(left + group_width / 2.0);
+                    continue;
+                }
+                for s in &series {
+                    let Some(v) = value(s, c) else { continue };
+                    let base = if v >= 0.0 { &mut up } else { &mut down };
+                    let from = *base;
+                    *base += v;
+                    // Segments too thin to hold a label are left unlabelled.
+                    let tall = (scale.y(from) - scale.y(*base)).abs() >= 14.0;
+                    bars.push(Bar {
+                        x: left,
+                        width: group_width,
+                        from,
+                        to: *base,
+                        color: s.color,
+                        label: tall.then(|| format_value(v, precision)),
+                    });
+                }
+                totals.push((
+                    left + group_width / 2.0,
+                    scale.y(up.max(0.0)) - 6.0,
+                    format_value(values.iter().sum(), precision),
+                ));
+            }
+        }
+    }
+
+    rsx! {
+        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
+            h3 { class: "text-xl font-bold text-brand-light mb-4", "{title}" }
+            svg {
+                class: "w-full flex-1 min-h-[250px]",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title}",
+
+                ValueAxis { ticks, unit }
+
+                // Bars, with zero-height bars kept visible as a baseline mark.
+                for bar in bars {
+                    rect {
+                        x: "{bar.x}",
+                        y: "{scale.y(bar.from.max(bar.to))}",
+                        width: "{bar.width}",
+                        height: "{(scale.y(bar.from.min(bar.to)) - scale.y(bar.from.max(bar.to))).max(1.5)}",
+                        fill: bar.color,
+                        rx: "2",
+                    }
+                    if let Some(label) = bar.label {
+                        text {
+                            x: "{bar.x + bar.width / 2.0}",
+                            y: if layout == BarLayout::Stacked {
+                                "{(scale.y(bar.from) + scale.y(bar.to)) / 2.0}"
+                            } else if bar.to < 0.0 {
+                                "{scale.y(bar.to) + 14.0}"
+                            } else {
+                                "{scale.y(bar.to) - 6.0}"
+                            },
+                            fill: if layout == BarLayout::Stacked { theme::DARK } else { theme::LIGHT },
+                            font_size: "13",
+                            font_weight: "bold",
+                            text_anchor: "middle",
+                            dominant_baseline: if layout == BarLayout::Stacked { "middle" } else { "auto" },
+                            "{label}"
+                        }
+                    }
+                }
+                for (x, y, total) in totals {
+                    text {
+                        x: "{x}",
+                        y: "{y}",
+                        fill: theme::LIGHT,
+                        font_size: "13",
+                        font_weight: "bold",
+                        text_anchor: "middle",
+                        "{total}"
+                    }
+                }
+
+                for x in gaps {
+                    text {
+                        x: "{x}",
+                        y: "{scale.y(0.0) - 6.0}",
+                        fill: theme::AXIS,
+                        font_size: "12",
+                        font_style: "italic",
+                        text_anchor: "middle",
+                        "n/a"
+                    }
+                }
+
+                // Category labels; "\n" starts a new line.
+                for (c, category) in categories.iter().enumerate() {
+                    text {
+                        x: "{MARGIN_LEFT + band * (c as f64 + 0.5)}",
+                        y: "{HEIGHT - MARGIN_BOTTOM + 18.0}",
+                        fill: theme::AXIS,
+                        font_size: "13",
+                        text_anchor: "middle",
+                        for (i, line) in category.split('\n').enumerate() {
+                            tspan {
+                                x: "{MARGIN_LEFT + band * (c as f64 + 0.5)}",
+                                dy: if i == 0 { "0" } else { "1.2em" },
+                                "{line}"
+                            }
+                        }
+                    }
+                }
+            }
+            if series.len() > 1 {
+                Legend { items: series.iter().map(|s| (s.name.clone(), s.color)).collect() }
+            }
+        }
+    }
+}
+
+/// Gridlines, tick labels and unit for a plot whose value range is `ticks`.
+#[component]
+fn ValueAxis(ticks: Vec<f64>, unit: String) -> Element {
+    let scale = Scale::from_ticks(&ticks);
+    // Enough decimals to tell adjacent ticks apart, and no more.
+    let precision = match ticks.as_slice() {
+        [a, b, ..] => (-(b - a).log10().floor()).max(0.0) as usize,
+        _ => 0,
+    };
+
+    rsx! {
+        for tick in ticks.iter().copied() {
+            line {
+                x1: "{MARGIN_LEFT}",
+                x2: "{WIDTH - MARGIN_RIGHT}",
+                y1: "{scale.y(tick)}",
+                y2: "{scale.y(tick)}",
+                stroke: theme::AXIS,
+                stroke_opacity: if tick == 0.0 { "0.6" } else { "0.15" },
+            }
+            text {
+                x: "{MARGIN_LEFT - 8.0}",
+                y: "{scale.y(tick)}",
+                fill: theme::AXIS,
+                font_size: "12",
+                text_anchor: "end",
+                dominant_baseline: "middle",
+                "{format_value(tick, precision)}"
+            }
+        }
+        if !unit.is_empty() {
+            text {
+                x: "{MARGIN_LEFT - 8.0}",
+                y: "{MARGIN_TOP - 14.0}",
+                fill: theme::AXIS,
+                font_size: "12",
+                text_anchor: "end",
+                "{unit}"
+            }
+        }
+    }
+}
+
+#[component]
+fn Legend(items: Vec<(String, &'static str)>) -> Element {
+    rsx! {
+        div { class: "mt-4 flex justify-center gap-6 text-sm",
+            for (name, color) in items {
+                div { class: "flex items-center gap-2",
+                    div { class: "w-3 h-3 rounded-sm", style: "background: {color}" }
+                    span { class: "text-zinc-400", "{name}" }
+                }
+            }
+        }
+    }
+}
+
+/// One subject in a paired plot, identified by `key` for selection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairedLine {
+    pub key: usize,
+    pub before: f64,
+    pub after: f64,
+    pub color: &'static str,
+}
+
+/// Before/after values joined by one line per subject, so individual
+/// trajectories stay visible. An optional median line is drawn on top, and
+/// clicking a line reports its key.
+#[component]
+pub fn PairedLinePlot(
+    title: String,
+    /// Axis labels for the two columns.
+    columns: (String, String),
+    lines: Vec<PairedLine>,
+    median: Option<(f64, f64)>,
+    #[props(default)] legend: Vec<(String, &'static str)>,
+    #[props(default)] unit: String,
+    selected: Option<usize>,
+    on_select: EventHandler<usize>,
+) -> Element {
+    let (low, high) = lines
+        .iter()
+        .flat_map(|l| [l.before, l.after])
+        .fold((0.0_f64, 0.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let ticks = nice_ticks(low, high, TARGET_TICKS);
+    let scale = Scale::from_ticks(&ticks);
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let (x_before, x_after) = (
+        MARGIN_LEFT + plot_width * 0.25,
+        MARGIN_LEFT + plot_width * 0.75,
+    );
+    let faded = selected.is_some();
+
+    rsx! {
+        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
+            h3 { class: "text-xl font-bold text-brand-light mb-4", "{title}" }
+            svg {
+                class: "w-full flex-1 min-h-[250px]",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title}",
+
+                ValueAxis { ticks, unit }
+                for line in lines {
+                    g {
+                        key: "{line.key}",
+                        class: "cursor-pointer",
+                        opacity: if selected == Some(line.key) { "1" } else if faded { "0.2" } else { "0.75" },
+                        onclick: move |_| on_select.call(line.key),
+                        // Wide transparent stroke so thin lines are easy to hit.
+                        line {
+                            x1: "{x_before}",
+                            y1: "{scale.y(line.before)}",
+                            x2: "{x_after}",
+                            y2: "{scale.y(line.after)}",
+                            stroke: "transparent",
+                            stroke_width: "12",
+                        }
+                        line {
+                            x1: "{x_before}",
+                            y1: "{scale.y(line.before)}",
+                            x2: "{x_after}",
+                            y2: "{scale.y(line.after)}",
+                            stroke: line.color,
+                            stroke_width: if selected == Some(line.key) { "4" } else { "2" },
+                        }
+                        circle { cx: "{x_before}", cy: "{scale.y(line.before)}", r: "3.5", fill: line.color }
+                        circle { cx: "{x_after}", cy: "{scale.y(line.after)}", r: "3.5", fill: line.color }
+                    }
+                }
+                if let Some((before, after)) = median {
+                    g { class: "pointer-events-none",
+                        line {
+                            x1: "{x_before}",
+                            y1: "{scale.y(before)}",
+                            x2: "{x_after}",
+                            y2: "{scale.y(after)}",
+                            stroke: theme::TAUPE,
+                            stroke_width: "6",
+                            stroke_linecap: "round",
+                        }
+                        text {
+                            x: "{x_after + 12.0}",
+                            y: "{scale.y(after)}",
+                            fill: theme::TAUPE,
+                            font_size: "13",
+                            font_weight: "bold",
+                            dominant_baseline: "middle",
+                            "Median {format_value(after, 1)}"
+                        }
+                        text {
+                            x: "{x_before - 12.0}",
+                            y: "{scale.y(before)}",
+                            fill: theme::TAUPE,
+                            font_size: "13",
+                            font_weight: "bold",
+                            text_anchor: "end",
+                            dominant_baseline: "middle",
+                            "{format_value(before, 1)}"
+                        }
+                    }
+                }
+                for (x, label) in [(x_before, columns.0), (x_after, columns.1)] {
+                    text {
+                        x: "{x}",
+                        y: "{HEIGHT - MARGIN_BOTTOM + 18.0}",
+                        fill: theme::AXIS,
+                        font_size: "13",
+                        text_anchor: "middle",
+                        "{label}"
+                    }
+                }
+            }
+            if !legend.is_empty() {
+                Legend { items: legend }
+            }
+        }
+    }
+}
+
+/// One bar of a waterfall chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterfallBar {
+    /// Reported when the bar is clicked; a bar without one is not clickable.
+    pub key: Option<usize>,
+    pub value: f64,
+    /// Drawn at the end of the bar.
+    pub label: String,
+    pub color: &'static str,
+}
+
+/// One bar per subject, in the order given, hanging from zero. Thresholds are
+/// drawn as labelled dashed reference lines. When `on_select` is set,
+/// clicking a bar with a key reports it.
+#[component]
+pub fn WaterfallChart(
+    title: String,
+    bars: Vec<WaterfallBar>,
+    #[props(default)] thresholds: Vec<(f64, String)>,
+    #[props(default)] legend: Vec<(String, &'static str)>,
+    #[props(default)] unit: String,
+    on_select: Option<EventHandler<usize>>,
+) -> Element {
+    let (low, high) = bars
+        .iter()
+        .map(|b| b.value)
+        .chain(thresholds.iter().map(|t| t.0))
+        .fold((0.0_f64, 0.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let ticks = nice_ticks(low, high, TARGET_TICKS);
+    let scale = Scale::from_ticks(&ticks);
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let band = plot_width / bars.len().max(1) as f64;
+    let width = (band * 0.8).max(1.0);
+
+    rsx! {
+        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
+            h3 { class: "text-xl font-bold text-brand-light mb-4", "{title}" }
+            svg {
+                class: "w-full flex-1 min-h-[250px]",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title}",
+
+                ValueAxis { ticks, unit }
+                for (i, bar) in bars.iter().enumerate() {
+                    rect {
+                        key: "{i}",
+                        x: "{MARGIN_LEFT + band * i as f64 + (band - width) / 2.0}",
+                        y: "{scale.y(bar.value.max(0.0))}",
+                        width: "{width}",
+                        height: "{(scale.y(bar.value.min(0.0)) - scale.y(bar.value.max(0.0))).max(1.5)}",
+                        fill: bar.color,
+                        rx: "1",
+                        class: if on_select.is_some() && bar.key.is_some() { "cursor-pointer" },
+                        onclick: {
+                            let key = bar.key;
+                            move |_| {
+                                if let (Some(on_select), Some(key)) = (on_select, key) {
+                                    on_select.call(key);
+                                }
+                            }
+                        },
+                    }
+                    text {
+                        x: "{MARGIN_LEFT + band * (i as f64 + 0.5)}",
+                        y: if bar.value < 0.0 { "{scale.y(bar.value) + 12.0}" } else { "{scale.y(bar.value) - 4.0}" },
+                        fill: theme::LIGHT,
+                        font_size: "10",
+                        text_anchor: "middle",
+                        "{bar.label}"
+                    }
+                }
+                for (value, label) in thresholds {
+                    line {
+                        x1: "{MARGIN_LEFT}",
+                        x2: "{WIDTH - MARGIN_RIGHT}",
+                        y1: "{scale.y(value)}",
+                        y2: "{scale.y(value)}",
+                        stroke: theme::TAUPE,
+                        stroke_width: "1.5",
+                        stroke_dasharray: "6 4",
+                    }
+                    text {
+                        x: "{WIDTH - MARGIN_RIGHT}",
+                        y: "{scale.y(value) - 4.0}",
+                        fill: theme::TAUPE,
+                        font_size: "11",
+                        text_anchor: "end",
+                        "{label}"
+                    }
+                }
+            }
+            if !legend.is_empty() {
+                Legend { items: legend }
+            }
+        }
+    }
+}
+
+/// One subject in a scatter plot, identified by `key` for selection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatterPoint {
+    pub key: usize,
+    pub x: f64,
+    pub y: f64,
+    pub color: &'static str,
+}
+
+/// One point per subject against two value axes, both including zero. An
+/// optional straight line, given as `(intercept, slope)`, is drawn across
+/// the plotted x range. When `on_select` is set, clicking a point reports
+/// its key.
+#[component]
+pub fn ScatterPlot(
+    title: String,
+    points: Vec<ScatterPoint>,
+    x_label: String,
+    line: Option<(f64, f64)>,
+    #[props(default)] legend: Vec<(String, &'static str)>,
+    #[props(default)] unit: String,
+    on_select: Option<EventHandler<usize>>,
+) -> Element {
+    let bounds = |values: &mut dyn Iterator<Item = f64>| {
+        values.fold((0.0_f64, 0.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)))
+    };
+    let (y_low, y_high) = bounds(&mut points.iter().map(|p| p.y));
+    let (x_low, x_high) = bounds(&mut points.iter().map(|p| p.x));
+    let ticks = nice_ticks(y_low, y_high, TARGET_TICKS);
+    let scale = Scale::from_ticks(&ticks);
+    let x_ticks = nice_ticks(x_low, x_high, TARGET_TICKS);
+    let x_scale = Scale::from_ticks(&x_ticks);
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let x =
+        move |v: f64| MARGIN_LEFT + plot_width * (v - x_scale.min) / (x_scale.max - x_scale.min);
+    let x_precision = match x_ticks.as_slice() {
+        [a, b, ..] => (-(b - a).log10().floor()).max(0.0) as usize,
+        _ => 0,
+    };
+    // Drawn over the data only, and clipped to the value axis.
+    let fitted = line.filter(|_| points.len() > 1).map(|(intercept, slope)| {
+        let from = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let to = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+        let y = |v: f64| (intercept + slope * v).clamp(scale.min, scale.max);
+        (x(from), scale.y(y(from)), x(to), scale.y(y(to)))
+    });
+
+    rsx! {
+        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
+            h3 { class: "text-xl font-bold text-brand-light mb-4", "{title}" }
+            svg {
+                class: "w-full flex-1 min-h-[250px]",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title}",
+
+                ValueAxis { ticks, unit }
+                for tick in x_ticks.iter().copied() {
+                    line {
+                        x1: "{x(tick)}",
+                        x2: "{x(tick)}",
+                        y1: "{MARGIN_TOP}",
+                        y2: "{HEIGHT - MARGIN_BOTTOM}",
+                        stroke: theme::AXIS,
+                        stroke_opacity: if tick == 0.0 { "0.6" } else { "0.15" },
+                    }
+                    text {
+                        x: "{x(tick)}",
+                        y: "{HEIGHT - MARGIN_BOTTOM + 16.0}",
+                        fill: theme::AXIS,
+                        font_size: "12",
+                        text_anchor: "middle",
+                        "{format_value(tick, x_precision)}"
+                    }
+                }
+                text {
+                    x: "{MARGIN_LEFT + plot_width / 2.0}",
+                    y: "{HEIGHT - 6.0}",
+                    fill: theme::AXIS,
+                    font_size: "12",
+                    text_anchor: "middle",
+                    "{x_label}"
+                }
+                if let Some((x1, y1, x2, y2)) = fitted {
+                    line {
+                        x1: "{x1}",
+                        y1: "{y1}",
+                        x2: "{x2}",
+                        y2: "{y2}",
+                        stroke: theme::TAUPE,
+                        stroke_width: "2",
+                        stroke_dasharray: "6 4",
+                        class: "pointer-events-none",
+                    }
+                }
+                for point in points {
+                    circle {
+                        key: "{point.key}",
+                        cx: "{x(point.x)}",
+                        cy: "{scale.y(point.y)}",
+                        r: "6",
+                        fill: point.color,
+                        fill_opacity: "0.85",
+                        stroke: theme::DARK,
+                        stroke_width: "1",
+                        class: if on_select.is_some() { "cursor-pointer" },
+                        onclick: move |_| {
+                            if let Some(on_select) = on_select {
+                                on_select.call(point.key);
+                            }
+                        },
+                    }
+                }
+            }
+            if !legend.is_empty() {
+                Legend { items: legend }
+            }
+        }
+    }
+}
+
+/// Point shape, so a second grouping can be read without relying on colour.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    #[default]
+    Circle,
+    Square,
+    Triangle,
+}
+
+/// One value on a timeline. Consecutive points with the same `run` are
+/// joined by a line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelinePoint {
+    pub x: f64,
+    pub value: f64,
+    pub run: usize,
+    pub marker: Marker,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineSeries {
+    pub name: String,
+    pub points: Vec<TimelinePoint>,
+    pub color: &'static str,
+}
+
+/// A labelled stretch of the x axis, shaded when `shaded` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineBand {
+    pub from: f64,
+    pub to: f64,
+    pub label: String,
+    pub shaded: bool,
+}
+
+/// Values against time, one coloured series per measure. The caller places
+/// points on the x axis, so an axis broken between sessions works as well
+/// as a continuous one. `x_labels` are thinned so they do not overlap.
+#[component]
+pub fn TimelineChart(
+    title: String,
+    series: Vec<TimelineSeries>,
+    #[props(default)] x_labels: Vec<(f64, String)>,
+    #[props(default)] bands: Vec<TimelineBand>,
+    /// Explains the point shapes in the legend.
+    #[props(default)]
+    markers: Vec<(Marker, String)>,
+    #[props(default)] unit: String,
+) -> Element {
+    let points = || series.iter().flat_map(|s| s.points.iter());
+    let high = points().map(|p| p.value).fold(0.0_f64, f64::max);
+    let low = points().map(|p| p.value).fold(0.0_f64, f64::min);
+    let ticks = nice_ticks(low, high, TARGET_TICKS);
+    let scale = Scale::from_ticks(&ticks);
+
+    let (x_min, x_max) = points()
+        .map(|p| p.x)
+        .chain(bands.iter().flat_map(|b| [b.from, b.to]))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| {
+            (lo.min(x), hi.max(x))
+        });
+    let (x_min, x_max) = if x_max > x_min {
+        (x_min, x_max)
+    } else {
+        (0.0, 1.0)
+    };
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let x = move |v: f64| MARGIN_LEFT + plot_width * (v - x_min) / (x_max - x_min);
+
+    let runs: Vec<(&'static str, String)> = series
+        .iter()
+        .flat_map(|s| {
+            s.points
+                .chunk_by(|a, b| a.run == b.run)
+                .filter(|run| run.len() > 1)
+                .map(|run| {
+                    let path = run
+                        .iter()
+                        .map(|p| format!("{},{}", x(p.x), scale.y(p.value)))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    (s.color, path)
+                })
+        })
+        .collect();
+
+    let mut labels: Vec<(f64, String)> = Vec::new();
+    for (at, label) in x_labels {
+        if labels.last().is_none_or(|(prev, _)| x(at) - prev >= 64.0) {
+            labels.push((x(at), label));
+        }
+    }
+
+    rsx! {
+        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
+            h3 { class: "text-xl font-bold text-brand-light mb-4", "{title}" }
+            svg {
+                class: "w-full flex-1 min-h-[250px]",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title}",
+
+                for band in bands.iter() {
+                    if band.shaded {
+                        rect {
+                            x: "{x(band.from)}",
+                            y: "{MARGIN_TOP}",
+                            width: "{x(band.to) - x(band.from)}",
+                            height: "{HEIGHT - MARGIN_TOP - MARGIN_BOTTOM}",
+                            fill: theme::TAUPE,
+                            fill_opacity: "0.08",
+                        }
+                    }
+                    text {
+                        x: "{(x(band.from) + x(band.to)) / 2.0}",
+                        y: "{MARGIN_TOP - 10.0}",
+                        fill: theme::AXIS,
+                        font_size: "12",
+                        text_anchor: "middle",
+                        "{band.label}"
+                    }
+                }
+                ValueAxis { ticks, unit }
+                for (color, path) in runs {
+                    polyline {
+                        points: "{path}",
+                        fill: "none",
+                        stroke: color,
+                        stroke_width: "1.5",
+                        stroke_opacity: "0.6",
+                    }
+                }
+                for s in series.iter() {
+                    for p in s.points.iter() {
+                        MarkerShape { marker: p.marker, x: x(p.x), y: scale.y(p.value), color: s.color }
+                    }
+                }
+                for (at, label) in labels {
+                    text {
+                        x: "{at}",
+                        y: "{HEIGHT - MARGIN_BOTTOM + 18.0}",
+                        fill: theme::AXIS,
+                        font_size: "12",
+                        text_anchor: "middle",
+                        "{label}"
+                    }
+                }
+            }
+            div { class: "flex justify-center gap-6",
+                if series.len() > 1 {
+                    Legend { items: series.iter().map(|s| (s.name.clone(), s.color)).collect() }
+                }
+                if !markers.is_empty() {
+                    div { class: "mt-4 flex justify-center gap-6 text-sm",
+                        for (marker, name) in markers {
+                            div { class: "flex items-center gap-2",
+                                svg { class: "w-3 h-3", view_box: "0 0 12 12",
+                                    MarkerShape { marker, x: 6.0, y: 6.0, color: theme::AXIS }
+                                }
+                                span { class: "text-zinc-400", "{name}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MarkerShape(marker: Marker, x: f64, y: f64, color: &'static str) -> Element {
+    match marker {
+        Marker::Circle => rsx! {
+            circle { cx: "{x}", cy: "{y}", r: "3.5", fill: color }
+        },
+        Marker::Square => rsx! {
+            rect { x: "{x - 3.5}", y: "{y - 3.5}", width: "7", height: "7", fill: color }
+        },
+        Marker::Triangle => rsx! {
+            polygon {
+                points: "{x},{y - 4.5} {x + 4.5},{y + 3.5} {x - 4.5},{y + 3.5}",
+                fill: color,
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nice_step_rounds_up_to_1_2_5() {
+        assert_eq!(nice_step(1.0), 1.0);
+        assert_eq!(nice_step(3.0), 5.0);
+        assert_eq!(nice_step(7.0), 10.0);
+        assert_eq!(nice_step(9.25), 10.0);
+        assert_eq!(nice_step(150.0), 200.0);
+        assert_eq!(nice_step(0.0), 1.0);
+        assert_eq!(nice_step(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn ticks_cover_the_range() {
+        assert_eq!(nice_ticks(0.0, 37.0, 5), [0.0, 10.0, 20.0, 30.0, 40.0]);
+        assert_eq!(nice_ticks(-12.0, 30.0, 5), [-20.0, 0.0, 20.0, 40.0]);
+    }
+
+    #[test]
+    fn degenerate_ranges_still_give_an_axis() {
+        assert_eq!(nice_ticks(0.0, 0.0, 5), [0.0, 0.5, 1.0]);
+        assert_eq!(nice_ticks(5.0, 5.0, 5), [5.0, 5.5, 6.0]);
+        // A reversed range is treated as empty at `min`.
+        assert_eq!(nice_ticks(3.0, 1.0, 5), nice_ticks(3.0, 3.0, 5));
+        // Fewer than two ticks asked for: one step spans the range.
+        assert_eq!(nice_ticks(0.0, 10.0, 1), [0.0, 10.0]);
+        assert_eq!(nice_ticks(0.0, 10.0, 0), [0.0, 10.0]);
+    }
+
+    #[test]
+    fn format_value_drops_negative_zero() {
+        assert_eq!(format_value(-0.04, 1), "0.0");
+        assert_eq!(format_value(-0.4, 0), "0");
+        assert_eq!(format_value(0.0, 0), "0");
+        assert_eq!(format_value(-1.26, 1), "-1.3");
+        assert_eq!(format_value(12.0, 0), "12");
+    }
+
+    #[test]
+    fn scale_maps_ticks_onto_the_plot_area() {
+        let scale = Scale::from_ticks(&[0.0, 10.0, 20.0, 30.0, 40.0]);
+        assert_eq!(scale.y(40.0), MARGIN_TOP);
+        assert_eq!(scale.y(0.0), HEIGHT - MARGIN_BOTTOM);
+        assert_eq!(scale.y(20.0), (MARGIN_TOP + HEIGHT - MARGIN_BOTTOM) / 2.0);
+        let empty = Scale::from_ticks(&[]);
+        assert_eq!((empty.min, empty.max), (0.0, 1.0));
+    }
+
+    #[component]
+    fn Waterfall(keys: Vec<Option<usize>>) -> Element {
+        let bars = keys
+            .into_iter()
+            .map(|key| WaterfallBar {
+                key,
+                value: -50.0,
+                label: String::new(),
+                color: theme::LIGHT,
+            })
+            .collect();
+        rsx! {
+            WaterfallChart { title: "MEQ", bars, on_select: |_| {} }
+        }
+    }
+
+    #[test]
+    fn only_bars_with_a_key_are_clickable() {
+        let mut dom = VirtualDom::new_with_props(
+            Waterfall,
+            WaterfallProps {
+                keys: vec![Some(3), None],
+            },
+        );
+        dom.rebuild_in_place();
+        let html = dioxus_ssr::render(&dom);
+        assert_eq!(html.matches("<rect").count(), 2);
+        assert_eq!(html.matches("cursor-pointer").count(), 1);
+    }
+}
@@ -0,0 +1,209 @@
+//! Keeps the audience deck and the presenter view on the same slide.
+//!
+//! On the web both windows share a `BroadcastChannel`; on desktop every
+//! window lives on the same main thread, so a thread-local subscriber list
+//! does the same job. Either way a message is delivered to every other
+//! window, never back to the sender. Besides the slide, the channel carries
+//! the data files loaded in either window; see
+//! [`crate::components::live_data`].
+
+use crate::components::fragments::DeckPosition;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeckMessage {
+    /// The sender is now showing this slide and fragment step.
+    Goto(DeckPosition),
+    /// Toggle the audience blackout screen.
+    Blackout,
+    /// A newly opened window asking the others to announce their position
+    /// and data.
+    Hello,
+    /// A data file the sender loaded, to be loaded over the receiver's data.
+    DataFile { name: String, bytes: Vec<u8> },
+    /// The sender went back to the bundled data.
+    DataReset,
+}
+
+impl DeckMessage {
+    /// The JSON posted on the `BroadcastChannel`.
+    pub fn encode(&self) -> Option<String> {
+        serde_json::to_string(self).ok()
+    }
+
+    /// A message posted by another window; `None` for anything else on the
+    /// channel.
+    pub fn decode(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+#[cfg(not(feature = "desktop"))]
+mod transport {
+    use super::DeckMessage;
+    use gloo_events::EventListener;
+    use wasm_bindgen::JsCast;
+    use web_sys::{BroadcastChannel, MessageEvent};
+
+    const CHANNEL_NAME: &str = "standup-deck";
+
+    pub struct DeckChannel {
+        channel: BroadcastChannel,
+        _listener: EventListener,
+    }
+
+    impl DeckChannel {
+        pub fn open(mut on_message: impl FnMut(DeckMessage) + 'static) -> Option<Self> {
+            let channel = BroadcastChannel::new(CHANNEL_NAME).ok()?;
+            let listener = EventListener::new(&channel, "message", move |event| {
+                let Some(event) = event.dyn_ref::<MessageEvent>() else {
+                    return;
+                };
+                let parsed = event
+                    .data()
+                    .as_string()
+                    .and_then(|json| DeckMessage::decode(&json));
+                if let Some(msg) = parsed {
+                    on_message(msg);
+                }
+            });
+            Some(Self {
+                channel,
+                _listener: listener,
+            })
+        }
+
+        pub fn post(&self, msg: DeckMessage) {
+            if let Some(json) = msg.encode() {
+                let _ = self.channel.post_message(&json.into());
+            }
+        }
+    }
+
+    impl Drop for DeckChannel {
+        fn drop(&mut self) {
+            self.channel.close();
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+mod transport {
+    use super::DeckMessage;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    type Handler = Rc<RefCell<dyn FnMut(DeckMessage)>>;
+
+    thread_local! {
+        static SUBSCRIBERS: RefCell<Vec<(usize, Handler)>> = const { RefCell::new(Vec::new()) };
+        static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub struct DeckChannel {
+        id: usize,
+    }
+
+    impl DeckChannel {
+        pub fn open(on_message: impl FnMut(DeckMessage) + 'static) -> Option<Self> {
+            let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+            let handler: Handler = Rc::new(RefCell::new(on_message));
+            SUBSCRIBERS.with(|s| s.borrow_mut().push((id, handler)));
+            Some(Self { id })
+        }
+
+        pub fn post(&self, msg: DeckMessage) {
+            // Collect first so handlers may post replies without re-borrowing.
+            let others: Vec<Handler> = SUBSCRIBERS.with(|s| {
+                s.borrow()
+                    .iter()
+                    .filter(|(id, _)| *id != self.id)
+                    .map(|(_, h)| h.clone())
+                    .collect()
+            });
+            for handler in others {
+                if let Ok(mut handler) = handler.try_borrow_mut() {
+                    handler(msg.clone());
+                }
+            }
+        }
+    }
+
+    impl Drop for DeckChannel {
+        fn drop(&mut self) {
+            SUBSCRIBERS.with(|s| s.borrow_mut().retain(|(id, _)| *id != self.id));
+        }
+    }
+}
+
+pub use transport::DeckChannel;
+
+/// Opens the deck channel once for the lifetime of the calling component.
+/// Returns `None` when the platform has no channel (e.g. BroadcastChannel is
+/// unavailable), in which case the window simply runs on its own.
+///
+/// Messages arrive outside of Dioxus, so the handler is wrapped in a
+/// `Callback` to run it inside this component's runtime.
+pub fn use_deck_channel(on_message: impl FnMut(DeckMessage) + 'static) -> Option<Rc<DeckChannel>> {
+    let on_message = use_callback(on_message);
+    use_hook(move || DeckChannel::open(move |msg| on_message.call(msg)).map(Rc::new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::fragments::ALL_STEPS;
+
+    #[test]
+    fn messages_round_trip() {
+        for msg in [
+            DeckMessage::Goto(DeckPosition { slide: 3, step: 1 }),
+            DeckMessage::Goto(DeckPosition {
+                slide: 0,
+                step: ALL_STEPS,
+            }),
+            DeckMessage::Blackout,
+            DeckMessage::Hello,
+            DeckMessage::DataFile {
+                name: "scores.csv".to_string(),
+                bytes: b"PAT_MRN_ID,CGI-I Score\nSU001,2\n".to_vec(),
+            },
+            DeckMessage::DataReset,
+        ] {
+            assert_eq!(
+                DeckMessage::decode(&msg.encode().unwrap()),
+                Some(msg.clone())
+            );
+        }
+    }
+
+    /// Both windows may run different builds mid-deploy, so the wire format
+    /// is pinned.
+    #[test]
+    fn wire_format() {
+        let goto = DeckMessage::Goto(DeckPosition { slide: 3, step: 1 });
+        assert_eq!(
+            goto.encode().unwrap(),
+            r#"{"type":"goto","slide":3,"step":1}"#
+        );
+        assert_eq!(
+            DeckMessage::Blackout.encode().unwrap(),
+            r#"{"type":"blackout"}"#
+        );
+        assert_eq!(
+            DeckMessage::decode(r#"{"type":"hello"}"#),
+            Some(DeckMessage::Hello)
+        );
+    }
+
+    #[test]
+    fn other_posts_are_ignored() {
+        assert_eq!(DeckMessage::decode(""), None);
+        assert_eq!(DeckMessage::decode("not json"), None);
+        assert_eq!(DeckMessage::decode(r#"{"type":"reload"}"#), None);
+        assert_eq!(DeckMessage::decode(r#"{"type":"goto","slide":3}"#), None);
+    }
+}
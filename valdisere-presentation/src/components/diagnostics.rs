@@ -0,0 +1,274 @@
+use crate::data::consistency::ConsistencyReport;
+use crate::data::ingest::DatasetSummary;
+use crate::data::medication::MeqCheck;
+use crate::data::missing::Missingness;
+use crate::data::records::Phase;
+use dioxus::prelude::*;
+
+fn mmhg(value: Option<f64>) -> String {
+    value.map_or_else(|| "blank".to_string(), |v| format!("{v:.1}"))
+}
+
+/// Overlay listing, per dataset, how many rows loaded, which were rejected
+/// and why, and which values were cleared from rows that were kept, then where the summary sheet disagrees with the readings and,
+/// once prescriptions are loaded, where the MEQ export disagrees with them,
+/// and last what each analysis is missing. Kept off the slide itself; the
+/// Results header toggles it.
+#[component]
+pub fn DiagnosticsPanel(
+    datasets: Vec<DatasetSummary>,
+    consistency: ConsistencyReport,
+    meq: Option<MeqCheck>,
+    missing: Vec<Missingness>,
+    on_close: EventHandler<MouseEvent>,
+) -> Element {
+    let unmatched: Vec<(String, &str)> = consistency
+        .only_in_summary
+        .iter()
+        .map(|id| (id.clone(), "in the summary, no readings"))
+        .chain(
+            consistency
+                .only_in_readings
+                .iter()
+                .map(|id| (id.clone(), "readings, not in the summary")),
+        )
+        .collect();
+
+    rsx! {
+        div { class: "fixed inset-0 z-[60] bg-black/70 backdrop-blur-sm flex items-center justify-center p-12",
+            onclick: move |e| on_close.call(e),
+            div { class: "w-full max-w-4xl max-h-full overflow-y-auto bg-zinc-900 rounded-2xl border border-white/10 p-8 space-y-6",
+                onclick: move |e| e.stop_propagation(),
+                div { class: "flex items-center justify-between",
+                    h2 { class: "text-2xl font-bold text-brand-light", "Data diagnostics" }
+                    button {
+                        class: "px-4 py-1 rounded-full text-sm text-brand-taupe border border-white/10 hover:border-brand-orange/50 cursor-pointer",
+                        onclick: move |e| on_close.call(e),
+                        "Close"
+                    }
+                }
+                for d in datasets {
+                    div { class: "space-y-2",
+                        div { class: "flex items-baseline justify-between",
+                            h3 { class: "text-lg font-semibold text-brand-orange", "{d.dataset}" }
+                            span { class: "text-sm font-mono text-brand-taupe",
+                                "{d.accepted} loaded · {d.rejected.len()} rejected · {d.cleared.len()} values cleared"
+                            }
+                        }
+                        if let Some(error) = &d.error {
+                            div { class: "p-3 rounded-lg bg-red-500/10 border border-red-500/30 text-red-300 text-sm", "{error}" }
+                        }
+                        if !d.rejected.is_empty() {
+                            table { class: "w-full text-sm",
+                                thead {
+                                    tr { class: "text-left text-zinc-500",
+                                        th { class: "py-1 pr-4 w-20", "Line" }
+                                        th { class: "py-1", "Reason" }
+                                    }
+                                }
+                                tbody {
+                                    for row in d.rejected.iter() {
+                                        tr { class: "border-t border-white/5",
+                                            td { class: "py-1 pr-4 font-mono text-zinc-400", "{row.line}" }
+                                            td { class: "py-1 text-brand-light", "{row.reason}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if !d.cleared.is_empty() {
+                            table { class: "w-full text-sm",
+                                thead {
+                                    tr { class: "text-left text-zinc-500",
+                                        th { class: "py-1 pr-4 w-20", "Line" }
+                                        th { class: "py-1", "Cleared, row kept" }
+                                    }
+                                }
+                                tbody {
+                                    for value in d.cleared.iter() {
+                                        tr { class: "border-t border-white/5",
+                                            td { class: "py-1 pr-4 font-mono text-zinc-400", "{value.line}" }
+                                            td { class: "py-1 text-brand-taupe", "{value.reason}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "space-y-2",
+                    div { class: "flex items-baseline justify-between",
+                        h3 { class: "text-lg font-semibold text-brand-orange", "Summary sheet vs readings" }
+                        span { class: "text-sm font-mono text-brand-taupe",
+                            "{consistency.checked} checked · {consistency.patients_differing()} differ by > {consistency.tolerance} mmHg"
+                        }
+                    }
+                    if consistency.is_consistent() {
+                        div { class: "text-sm text-brand-taupe", "Every summary value matches the readings." }
+                    }
+                    if !consistency.discrepancies.is_empty() {
+                        table { class: "w-full text-sm",
+                            thead {
+                                tr { class: "text-left text-zinc-500",
+                                    th { class: "py-1 pr-4 w-20", "Patient" }
+                                    th { class: "py-1 pr-4", "Value" }
+                                    th { class: "py-1 pr-4 text-right", "Summary" }
+                                    th { class: "py-1 pr-4 text-right", "Readings" }
+                                    th { class: "py-1 text-right", "Difference" }
+                                }
+                            }
+                            tbody {
+                                for d in consistency.discrepancies.iter() {
+                                    tr { class: "border-t border-white/5",
+                                        td { class: "py-1 pr-4 font-mono text-zinc-400",
+                                            if let Some(n) = d.number { "{n}" } else { "{d.mrn}" }
+                                        }
+                                        td { class: "py-1 pr-4 text-brand-light", "{d.measure}" }
+                                        td { class: "py-1 pr-4 text-right font-mono text-brand-light", "{mmhg(d.summary)}" }
+                                        td { class: "py-1 pr-4 text-right font-mono text-brand-light", "{mmhg(d.recomputed)}" }
+                                        td { class: "py-1 text-right font-mono text-brand-orange",
+                                            if let Some(x) = d.difference() { "{x:+.1}" } else { "—" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if !unmatched.is_empty() {
+                        table { class: "w-full text-sm",
+                            thead {
+                                tr { class: "text-left text-zinc-500",
+                                    th { class: "py-1 pr-4 w-20", "ID" }
+                                    th { class: "py-1", "Found in" }
+                                }
+                            }
+                            tbody {
+                                for (id, found) in unmatched {
+                                    tr { class: "border-t border-white/5",
+                                        td { class: "py-1 pr-4 font-mono text-zinc-400", "{id}" }
+                                        td { class: "py-1 text-brand-light", "{found}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(meq) = meq {
+                    MeqSection { check: meq }
+                }
+                MissingSection { analyses: missing }
+            }
+        }
+    }
+}
+
+/// Stored MEQ against the MEQ recomputed from the prescriptions.
+#[component]
+fn MeqSection(check: MeqCheck) -> Element {
+    let unmatched: Vec<(String, &str)> = check
+        .only_stored
+        .iter()
+        .map(|id| (id.clone(), "MEQ export, no prescriptions"))
+        .chain(
+            check
+                .only_prescribed
+                .iter()
+                .map(|id| (id.clone(), "prescriptions, not in the MEQ export")),
+        )
+        .collect();
+
+    rsx! {
+        div { class: "space-y-2",
+            div { class: "flex items-baseline justify-between",
+                h3 { class: "text-lg font-semibold text-brand-orange", "MEQ vs prescriptions" }
+                span { class: "text-sm font-mono text-brand-taupe",
+                    "{check.checked} checked · {check.patients_differing()} differ by > {check.tolerance} ME"
+                }
+            }
+            if check.is_consistent() {
+                div { class: "text-sm text-brand-taupe", "Every stored MEQ matches the prescriptions." }
+            }
+            if !check.discrepancies.is_empty() {
+                table { class: "w-full text-sm",
+                    thead {
+                        tr { class: "text-left text-zinc-500",
+                            th { class: "py-1 pr-4 w-20", "ID" }
+                            th { class: "py-1 pr-4", "Phase" }
+                            th { class: "py-1 pr-4 text-right", "Stored" }
+                            th { class: "py-1 pr-4 text-right", "Computed" }
+                            th { class: "py-1 text-right", "Difference" }
+                        }
+                    }
+                    tbody {
+                        for d in check.discrepancies.iter() {
+                            tr { class: "border-t border-white/5",
+                                td { class: "py-1 pr-4 font-mono text-zinc-400", "{d.mrn}" }
+                                td { class: "py-1 pr-4 text-brand-light",
+                                    if d.phase == Phase::Pre { "pre" } else { "post" }
+                                }
+                                td { class: "py-1 pr-4 text-right font-mono text-brand-light", "{d.stored:.1}" }
+                                td { class: "py-1 pr-4 text-right font-mono text-brand-light", "{d.computed:.1}" }
+                                td { class: "py-1 text-right font-mono text-brand-orange", "{d.difference():+.1}" }
+                            }
+                        }
+                    }
+                }
+            }
+            if !unmatched.is_empty() {
+                table { class: "w-full text-sm",
+                    thead {
+                        tr { class: "text-left text-zinc-500",
+                            th { class: "py-1 pr-4 w-20", "ID" }
+                            th { class: "py-1", "Found in" }
+                        }
+                    }
+                    tbody {
+                        for (id, found) in unmatched {
+                            tr { class: "border-t border-white/5",
+                                td { class: "py-1 pr-4 font-mono text-zinc-400", "{id}" }
+                                td { class: "py-1 text-brand-light", "{found}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Per analysis, the patients left out or only partly covered, and what
+/// they lack.
+#[component]
+fn MissingSection(analyses: Vec<Missingness>) -> Element {
+    rsx! {
+        div { class: "space-y-2",
+            h3 { class: "text-lg font-semibold text-brand-orange", "Missing data" }
+            table { class: "w-full text-sm",
+                thead {
+                    tr { class: "text-left text-zinc-500",
+                        th { class: "py-1 pr-4 w-40", "Analysis" }
+                        th { class: "py-1 pr-4 w-20", "ID" }
+                        th { class: "py-1", "Missing" }
+                    }
+                }
+                tbody {
+                    for a in analyses.iter() {
+                        tr { class: "border-t border-white/10",
+                            td { class: "py-1 pr-4 text-brand-light", "{a.analysis}" }
+                            td { class: "py-1 text-brand-taupe font-mono", colspan: "2",
+                                "{a.complete()} of {a.patients} {a.base} complete"
+                            }
+                        }
+                        for entry in a.missing.iter() {
+                            tr { class: "border-t border-white/5",
+                                td {}
+                                td { class: "py-1 pr-4 font-mono text-zinc-400", "{entry.mrn}" }
+                                td { class: "py-1 text-brand-light", "{entry.what}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Incremental builds within a slide.
+//!
+//! Wrap parts of a slide in `Fragment { step: n, .. }` and they stay hidden
+//! until the deck has stepped to `n`. Fragments register their step with the
+//! slide they are rendered in, so the deck learns how many steps a slide has
+//! simply by showing it; nothing has to be declared in the registry.
+
+use crate::SLIDES;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Step value meaning "every fragment revealed", used when stepping back into
+/// a slide before its fragment count is known.
+pub const ALL_STEPS: usize = usize::MAX;
+
+/// Highest fragment step seen on each slide, indexed like `SLIDES`.
+pub static FRAGMENT_COUNTS: GlobalSignal<Vec<usize>> = Signal::global(|| vec![0; SLIDES.len()]);
+
+/// Where the deck is: a slide and how many of its fragments are showing.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DeckPosition {
+    pub slide: usize,
+    pub step: usize,
+}
+
+impl DeckPosition {
+    pub fn start_of(slide: usize) -> Self {
+        Self { slide, step: 0 }
+    }
+
+    /// This position if it is on `slide`, otherwise the start of `slide`.
+    pub fn on(self, slide: usize) -> Self {
+        if self.slide == slide {
+            self
+        } else {
+            Self::start_of(slide)
+        }
+    }
+
+    fn fragment_count(self) -> usize {
+        FRAGMENT_COUNTS.read().get(self.slide).copied().unwrap_or(0)
+    }
+
+    /// Reveals the next fragment, or moves to the start of the next slide
+    /// once every fragment is showing.
+    pub fn next(self) -> Option<Self> {
+        let count = self.fragment_count();
+        let step = self.step.min(count);
+        if step < count {
+            Some(Self {
+                step: step + 1,
+                ..self
+            })
+        } else if self.slide + 1 < SLIDES.len() {
+            Some(Self::start_of(self.slide + 1))
+        } else {
+            None
+        }
+    }
+
+    /// Hides the last fragment, or moves to the previous slide with all of
+    /// its fragments showing.
+    pub fn prev(self) -> Option<Self> {
+        let step = self.step.min(self.fragment_count());
+        if step > 0 {
+            Some(Self {
+                step: step - 1,
+                ..self
+            })
+        } else {
+            self.slide.checked_sub(1).map(|slide| Self {
+                slide,
+                step: ALL_STEPS,
+            })
+        }
+    }
+
+    /// Progress through this slide's fragments for display, e.g. `(2, 5)`.
+    pub fn progress(self) -> (usize, usize) {
+        let count = self.fragment_count();
+        (self.step.min(count), count)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FragmentView {
+    slide: usize,
+    step: usize,
+}
+
+/// Tells the fragments below it which slide they belong to and how far the
+/// deck has stepped. Slides rendered outside a scope show every fragment.
+#[component]
+pub fn FragmentScope(slide: usize, step: usize, children: Element) -> Element {
+    let view = use_memo(use_reactive!(|(slide, step)| FragmentView { slide, step }));
+    use_context_provider(|| view);
+
+    children
+}
+
+#[component]
+pub fn Fragment(step: usize, #[props(default)] class: String, children: Element) -> Element {
+    let view = try_use_context::<Memo<FragmentView>>();
+
+    use_effect(move || {
+        if let Some(view) = view {
+            let slide = view.peek().slide;
+            let known = FRAGMENT_COUNTS.peek().get(slide).copied();
+            if known.is_some_and(|count| count < step) {
+                FRAGMENT_COUNTS.write()[slide] = step;
+            }
+        }
+    });
+
+    let visible = view.is_none_or(|v| v().step >= step);
+    let state_class = if visible {
+        "opacity-100 translate-y-0"
+    } else {
+        "opacity-0 translate-y-4 pointer-events-none"
+    };
+
+    rsx! {
+        div { class: "{class} transition-all duration-500 {state_class}",
+            {children}
+        }
+    }
+}
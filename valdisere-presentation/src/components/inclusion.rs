@@ -0,0 +1,73 @@
+use crate::data::cohort::{Cohort, DATASETS};
+use dioxus::prelude::*;
+
+/// Overlay showing which datasets each patient contributes to, so every N on
+/// the Results slide can be traced back to the bundled exports. Patients are
+/// shown as anonymous columns; MRNs never reach the screen.
+#[component]
+pub fn InclusionPanel(cohort: Cohort, on_close: EventHandler<MouseEvent>) -> Element {
+    rsx! {
+        div { class: "fixed inset-0 z-[60] bg-black/70 backdrop-blur-sm flex items-center justify-center p-12",
+            onclick: move |e| on_close.call(e),
+            div { class: "w-full max-w-4xl max-h-full overflow-y-auto bg-zinc-900 rounded-2xl border border-white/10 p-8 space-y-6",
+                onclick: move |e| e.stop_propagation(),
+                div { class: "flex items-center justify-between",
+                    h2 { class: "text-2xl font-bold text-brand-light", "Patient inclusion" }
+                    button {
+                        class: "px-4 py-1 rounded-full text-sm text-brand-taupe border border-white/10 hover:border-brand-orange/50 cursor-pointer",
+                        onclick: move |e| on_close.call(e),
+                        "Close"
+                    }
+                }
+                div { class: "grid grid-cols-4 gap-4 text-center",
+                    CountTile { label: "Any dataset", value: cohort.n_total() }
+                    CountTile { label: "Paired BP", value: cohort.n_bp() }
+                    CountTile { label: "MEQ", value: cohort.n_meq() }
+                    CountTile { label: "CGI-I", value: cohort.n_cgi() }
+                }
+                table { class: "w-full text-sm",
+                    thead {
+                        tr { class: "text-left text-zinc-500",
+                            th { class: "py-1", "Datasets" }
+                            th { class: "py-1 w-20 text-right", "Patients" }
+                        }
+                    }
+                    tbody {
+                        for (inclusion, count) in cohort.breakdown() {
+                            tr { class: "border-t border-white/5",
+                                td { class: "py-1 text-brand-light", "{inclusion.label()}" }
+                                td { class: "py-1 text-right font-mono text-zinc-400", "{count}" }
+                            }
+                        }
+                    }
+                }
+                div { class: "space-y-1",
+                    for (row, label) in DATASETS.iter().enumerate() {
+                        div { class: "flex items-center gap-3",
+                            span { class: "w-16 shrink-0 text-xs text-zinc-500", "{label}" }
+                            div { class: "flex gap-1",
+                                for inclusion in cohort.inclusions() {
+                                    if inclusion.flags()[row] {
+                                        div { class: "w-3 h-3 rounded-sm bg-brand-orange" }
+                                    } else {
+                                        div { class: "w-3 h-3 rounded-sm bg-zinc-800" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CountTile(label: &'static str, value: usize) -> Element {
+    rsx! {
+        div { class: "p-4 rounded-xl bg-zinc-800/60 border border-white/5",
+            div { class: "text-3xl font-mono font-bold text-brand-light", "{value}" }
+            div { class: "text-xs uppercase tracking-widest text-zinc-500", "{label}" }
+        }
+    }
+}
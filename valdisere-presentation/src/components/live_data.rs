@@ -0,0 +1,308 @@
+//! The datasets behind the Results charts, shared so that a file dropped on
+//! or opened from the slide swaps the data everywhere at once.
+//!
+//! The context starts with the bundled exports. Each window has its own, kept
+//! in step over the deck channel: a file loaded in one window is posted to
+//! the others and loaded there too, and a window that opens later is sent
+//! every file loaded so far. The presenter's previews therefore show the
+//! numbers the audience sees.
+
+use crate::components::deck_sync::{use_deck_channel, DeckChannel, DeckMessage};
+use crate::data::datasets::Datasets;
+use dioxus::html::FileData;
+use dioxus::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+/// What the file picker offers; anything else is still tried as CSV.
+const ACCEPT: &str = ".csv,.ods,.xlsx,.xlsm,.xls";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveData {
+    pub datasets: Datasets,
+    /// The file each replaced dataset came from, by dataset name. Datasets
+    /// still bundled have no entry.
+    pub sources: BTreeMap<&'static str, String>,
+    /// Why the last file could not be used, until the next one loads.
+    pub error: Option<String>,
+    /// The files loaded over the bundled data, in order, so that another
+    /// window can load them too.
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl LiveData {
+    pub fn bundled() -> Self {
+        Self {
+            datasets: Datasets::bundled(),
+            sources: BTreeMap::new(),
+            error: None,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn is_bundled(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Short description of what is live, for the Results header.
+    pub fn label(&self) -> String {
+        let files: BTreeSet<&str> = self.sources.values().map(String::as_str).collect();
+        let files = files.into_iter().collect::<Vec<_>>().join(", ");
+        if self.is_bundled() {
+            "Bundled data".to_string()
+        } else if Datasets::BUNDLED
+            .iter()
+            .all(|dataset| self.sources.contains_key(dataset))
+        {
+            files
+        } else {
+            format!("{files} + bundled")
+        }
+    }
+
+    /// Loads a file over the current datasets. On error the data is left as
+    /// it was and the error kept for the header; returns whether it loaded.
+    pub fn load(&mut self, name: &str, bytes: Vec<u8>) -> bool {
+        let mut datasets = self.datasets.clone();
+        match datasets.load_file(name, bytes.clone()) {
+            Ok(replaced) => {
+                self.datasets = datasets;
+                for dataset in replaced {
+                    self.sources.insert(dataset, name.to_string());
+                }
+                self.files.push((name.to_string(), bytes));
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(format!("{name}: {e}"));
+                false
+            }
+        }
+    }
+}
+
+/// Provides the shared datasets; called once, at the root of the app.
+pub fn use_live_data_provider() -> Signal<LiveData> {
+    use_context_provider(|| Signal::new(LiveData::bundled()))
+}
+
+pub fn use_live_data() -> Signal<LiveData> {
+    use_context()
+}
+
+/// The channel the live data is shared over, when the window has one.
+#[derive(Clone)]
+struct DataChannel(Option<Rc<DeckChannel>>);
+
+impl DataChannel {
+    fn post(&self, msg: DeckMessage) {
+        if let Some(channel) = &self.0 {
+            channel.post(msg);
+        }
+    }
+}
+
+/// Keeps this window's live data in step with the other windows'; called
+/// once per window, below [`use_live_data_provider`]. Files posted by
+/// another window are loaded without being posted back.
+pub fn use_live_data_sync() {
+    let mut live = use_live_data();
+    // Bumped when another window says hello, so our files are sent to it.
+    let mut announce = use_signal(|| 0u32);
+    let channel = use_deck_channel(move |msg| match msg {
+        DeckMessage::DataFile { name, bytes } => {
+            live.write().load(&name, bytes);
+        }
+        DeckMessage::DataReset => live.set(LiveData::bundled()),
+        DeckMessage::Hello => *announce.write() += 1,
+        DeckMessage::Goto(_) | DeckMessage::Blackout => {}
+    });
+    let channel = use_context_provider(|| DataChannel(channel));
+
+    use_effect(move || {
+        if announce() == 0 {
+            return;
+        }
+        let live = live.peek();
+        if live.is_bundled() {
+            return;
+        }
+        channel.post(DeckMessage::DataReset);
+        for (name, bytes) in &live.files {
+            channel.post(DeckMessage::DataFile {
+                name: name.clone(),
+                bytes: bytes.clone(),
+            });
+        }
+    });
+}
+
+/// Reads each file and replaces the datasets it holds, posting it to the
+/// other windows once it has loaded. Files are applied in order, so a later
+/// file wins where two hold the same dataset.
+async fn load_files(
+    mut live: Signal<LiveData>,
+    channel: Option<DataChannel>,
+    files: Vec<FileData>,
+) {
+    for file in files {
+        let name = file.name();
+        let bytes = match file.read_bytes().await {
+            Ok(bytes) => bytes.to_vec(),
+            Err(e) => {
+                live.write().error = Some(format!("{name}: {e}"));
+                continue;
+            }
+        };
+        if live.write().load(&name, bytes.clone()) {
+            if let Some(channel) = &channel {
+                channel.post(DeckMessage::DataFile { name, bytes });
+            }
+        }
+    }
+}
+
+/// Goes back to the bundled data, here and in the other windows.
+fn reset(mut live: Signal<LiveData>, channel: Option<DataChannel>) {
+    live.set(LiveData::bundled());
+    if let Some(channel) = channel {
+        channel.post(DeckMessage::DataReset);
+    }
+}
+
+/// Wraps a slide so that a CSV or workbook dropped anywhere on it is loaded.
+#[component]
+pub fn DataDropZone(children: Element) -> Element {
+    let live = use_live_data();
+    let channel = try_use_context::<DataChannel>();
+    let mut dragging = use_signal(|| false);
+
+    rsx! {
+        div { class: "relative flex flex-col flex-1",
+            ondragover: move |evt| {
+                evt.prevent_default();
+                dragging.set(true);
+            },
+            ondragleave: move |_| dragging.set(false),
+            ondrop: move |evt| {
+                evt.prevent_default();
+                dragging.set(false);
+                spawn(load_files(live, channel.clone(), evt.data_transfer().files()));
+            },
+            {children}
+            if dragging() {
+                div { class: "absolute inset-4 z-50 rounded-2xl border-2 border-dashed border-brand-orange bg-brand-dark/80 flex items-center justify-center pointer-events-none",
+                    span { class: "text-2xl font-bold text-brand-orange", "Drop a CSV export or study workbook" }
+                }
+            }
+        }
+    }
+}
+
+/// Which dataset is live, with a picker to open another and a way back to
+/// the bundled data. On desktop the picker is the native file dialog.
+#[component]
+pub fn DataSourceBadge() -> Element {
+    let live = use_live_data();
+    let channel = try_use_context::<DataChannel>();
+    let reset_channel = channel.clone();
+    let state = live.read();
+    let bundled = state.is_bundled();
+    let label = state.label();
+    let title = if bundled {
+        "Compiled into the deck".to_string()
+    } else {
+        state
+            .sources
+            .iter()
+            .map(|(dataset, file)| format!("{dataset}: {file}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let error = state.error.clone();
+    drop(state);
+
+    rsx! {
+        div { class: "flex items-center gap-2 text-xs",
+            if let Some(error) = error {
+                span { class: "max-w-xs truncate text-red-300", title: "{error}", "{error}" }
+            }
+            span {
+                class: if bundled { "px-3 py-1 rounded-full border border-white/10 text-brand-taupe" } else { "px-3 py-1 rounded-full border border-brand-orange/50 bg-brand-orange/10 text-brand-orange font-semibold" },
+                title: "{title}",
+                "{label}"
+            }
+            label { class: "px-3 py-1 rounded-full border border-white/10 text-brand-taupe hover:text-brand-light cursor-pointer",
+                title: "Open a CSV export or study workbook",
+                "Open…"
+                input {
+                    class: "hidden",
+                    r#type: "file",
+                    accept: ACCEPT,
+                    multiple: true,
+                    onchange: move |evt| {
+                        spawn(load_files(live, channel.clone(), evt.files()));
+                    },
+                }
+            }
+            if !bundled {
+                button {
+                    class: "px-3 py-1 rounded-full border border-white/10 text-brand-taupe hover:text-brand-light cursor-pointer",
+                    onclick: move |_| reset(live, reset_channel.clone()),
+                    "Reset"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCORES: &[u8] = b"PAT_MRN_ID,CGI-I Score\nSU001,2\nSU002,4\n";
+
+    #[test]
+    fn a_loaded_file_is_kept_for_other_windows() {
+        let mut live = LiveData::bundled();
+        assert!(live.load("scores.csv", SCORES.to_vec()));
+        assert_eq!(live.datasets.cgi.records.len(), 2);
+        assert_eq!(live.files, [("scores.csv".to_string(), SCORES.to_vec())]);
+        assert_eq!(live.label(), "scores.csv + bundled");
+
+        // Replaying the files on a fresh copy gives the same data.
+        let mut other = LiveData::bundled();
+        for (name, bytes) in live.files.clone() {
+            other.load(&name, bytes);
+        }
+        assert_eq!(other, live);
+    }
+
+    #[test]
+    fn a_file_that_fails_changes_nothing() {
+        let mut live = LiveData::bundled();
+        assert!(!live.load("notes.csv", b"Note\nhello\n".to_vec()));
+        assert!(live.is_bundled());
+        assert!(live.files.is_empty());
+        assert!(live.error.unwrap().starts_with("notes.csv: "));
+    }
+
+    #[test]
+    fn label_says_bundled_until_every_bundled_dataset_is_replaced() {
+        let mut live = LiveData::bundled();
+        assert_eq!(live.label(), "Bundled data");
+        for dataset in Datasets::BUNDLED {
+            live.sources
+                .insert(dataset, "standup_results.ods".to_string());
+        }
+        assert_eq!(live.label(), "standup_results.ods");
+        live.sources.remove(Datasets::BUNDLED[2]);
+        live.sources
+            .insert("Prescriptions", "prescriptions.csv".to_string());
+        assert_eq!(
+            live.label(),
+            "prescriptions.csv, standup_results.ods + bundled"
+        );
+    }
+}
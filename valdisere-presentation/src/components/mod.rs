@@ -1,2 +1,10 @@
+pub mod chart;
+pub mod deck_sync;
+pub mod diagnostics;
+pub mod fragments;
+pub mod inclusion;
+pub mod live_data;
 pub mod navbar;
+pub mod patient;
+pub mod presenter;
 pub mod slide_deck;
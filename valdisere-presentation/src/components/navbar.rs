@@ -1,27 +1,35 @@
-use crate::Route;
+use crate::{registry, Route};
 use dioxus::prelude::*;
 
 #[component]
 pub fn NavBar() -> Element {
     let current_route = use_route::<Route>();
 
+    // Pair each link with whether it starts a new section, so a divider can
+    // be drawn between groups.
+    let links: Vec<_> = registry::nav_slides()
+        .scan(None, |prev, slide| {
+            let new_section = prev.is_some_and(|p| p != slide.section);
+            *prev = Some(slide.section);
+            Some((slide, new_section))
+        })
+        .collect();
+
     rsx! {
         nav { class: "fixed top-0 left-0 right-0 h-16 bg-zinc-950/90 backdrop-blur-md border-b border-white/10 flex items-center justify-between px-8 z-50 transition-all",
             // Brand / Logo Area
-            Link { to: Route::Intro {}, class: "font-black text-xl tracking-tighter text-transparent bg-clip-text bg-gradient-to-r from-brand-orange to-red-500 hover:brightness-125 transition-all select-none",
+            Link { to: registry::first_slide().route.clone(), class: "font-black text-xl tracking-tighter text-transparent bg-clip-text bg-gradient-to-r from-brand-orange to-red-500 hover:brightness-125 transition-all select-none",
                 "STANDUP II"
             }
 
             // Navigation Links
             div { class: "flex items-center gap-1",
-                NavLink { to: Route::Intro {}, label: "Home", active: current_route == Route::Intro {} }
-                NavLink { to: Route::Motivation {}, label: "Motivation", active: current_route == Route::Motivation {} }
-                NavLink { to: Route::Background {}, label: "Background", active: current_route == Route::Background {} }
-                NavLink { to: Route::Rationale {}, label: "Rationale", active: current_route == Route::Rationale {} }
-                NavLink { to: Route::Methods {}, label: "Methods", active: current_route == Route::Methods {} }
-                NavLink { to: Route::Results {}, label: "Results", active: current_route == Route::Results {} }
-                NavLink { to: Route::Discussion {}, label: "Discussion", active: current_route == Route::Discussion {} }
-                NavLink { to: Route::CaseVideo {}, label: "Case Video", active: current_route == Route::CaseVideo {} }
+                for (slide, new_section) in links {
+                    if new_section {
+                        div { class: "w-px h-6 bg-white/10 mx-2" }
+                    }
+                    NavLink { to: slide.route.clone(), label: slide.nav_label, active: current_route == slide.route }
+                }
             }
 
             // Right Side (Optional)
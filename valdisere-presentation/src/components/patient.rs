@@ -0,0 +1,347 @@
+//! One patient's readings, medication and clinical rating on a page of
+//! their own, reached from the per-patient charts on the Results slide.
+//! Patients appear by study number only, never by MRN.
+
+use crate::components::chart::{
+    theme, Marker, TimelineBand, TimelineChart, TimelinePoint, TimelineSeries,
+};
+use crate::components::live_data::use_live_data;
+use crate::components::slide_deck::{use_deck_keys, DeckCommand};
+use crate::data::cgi;
+use crate::data::medication::MeqResponse;
+use crate::data::orthostatic::sessions;
+use crate::data::records::{BpRecord, Phase, Position};
+use crate::Route;
+use dioxus::prelude::*;
+
+/// Share of a session's slot on the time axis taken by its readings; the
+/// rest separates it from its neighbours.
+const SESSION_WIDTH: f64 = 0.7;
+
+fn marker(position: Position) -> Marker {
+    match position {
+        Position::Lying => Marker::Circle,
+        Position::Sitting => Marker::Square,
+        Position::Standing => Marker::Triangle,
+    }
+}
+
+/// A patient's readings on an axis broken between sessions. Stand tests last
+/// minutes and are weeks apart, so session `i` gets the slot `[i, i + 1)`
+/// and its readings are spread across it by the time they were taken.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Timeline {
+    systolic: Vec<TimelinePoint>,
+    diastolic: Vec<TimelinePoint>,
+    heart_rate: Vec<TimelinePoint>,
+    labels: Vec<(f64, String)>,
+    bands: Vec<TimelineBand>,
+    sessions: usize,
+}
+
+impl Timeline {
+    fn build(records: &[BpRecord]) -> Self {
+        let mut timeline = Self::default();
+        let sessions = sessions(records);
+        for (i, session) in sessions.iter().enumerate() {
+            let start = i as f64 + (1.0 - SESSION_WIDTH) / 2.0;
+            let length = session
+                .readings
+                .last()
+                .map_or(0, |last| (last.time - session.start).num_seconds());
+            for r in &session.readings {
+                let x = if length > 0 {
+                    let taken = (r.time - session.start).num_seconds() as f64;
+                    start + SESSION_WIDTH * taken / length as f64
+                } else {
+                    i as f64 + 0.5
+                };
+                let point = |value: i32| TimelinePoint {
+                    x,
+                    value: f64::from(value),
+                    run: i,
+                    marker: marker(r.pos),
+                };
+                timeline.systolic.extend(r.sys.map(point));
+                timeline.diastolic.extend(r.dia.map(point));
+                timeline.heart_rate.extend(r.hr.map(point));
+            }
+            timeline
+                .labels
+                .push((i as f64 + 0.5, session.start.format("%d %b %y").to_string()));
+        }
+
+        // Sessions come before-then-after the procedure.
+        let n_pre = sessions.iter().filter(|s| s.phase == Phase::Pre).count();
+        let n_all = sessions.len();
+        if n_pre > 0 {
+            timeline.bands.push(TimelineBand {
+                from: 0.0,
+                to: n_pre as f64,
+                label: "Pre-Intervention".to_string(),
+                shaded: false,
+            });
+        }
+        if n_all > n_pre {
+            timeline.bands.push(TimelineBand {
+                from: n_pre as f64,
+                to: n_all as f64,
+                label: "Post-Intervention".to_string(),
+                shaded: true,
+            });
+        }
+        timeline.sessions = n_all;
+        timeline
+    }
+}
+
+#[component]
+pub fn PatientView(id: usize) -> Element {
+    let nav = use_navigator();
+    let live = use_live_data();
+    let datasets = use_memo(move || live.read().datasets.clone());
+    let cohort = use_memo(move || datasets.read().cohort());
+    let count = cohort.read().n_bp();
+
+    let go = use_callback(move |number: usize| {
+        if (1..=count).contains(&number) {
+            nav.push(Route::PatientView { id: number });
+        }
+    });
+    // The deck keymap, applied to patients: arrows step, Home/End go to the
+    // ends and a number followed by Enter jumps to that patient.
+    let jump_digits = use_deck_keys(move |command| match command {
+        DeckCommand::Next => go(id + 1),
+        DeckCommand::Prev => go(id.saturating_sub(1)),
+        DeckCommand::First => go(1),
+        DeckCommand::Last => go(count),
+        DeckCommand::Goto(i) => go(i + 1),
+        DeckCommand::Blackout | DeckCommand::OpenPresenter => {}
+    });
+
+    let data = datasets.read();
+    let Some(mrn) = cohort.read().patient_mrn(id).map(str::to_string) else {
+        return rsx! {
+            document::Title { "STANDUP II" }
+            div { class: "min-h-screen flex items-center justify-center bg-brand-dark",
+                div { class: "text-center",
+                    h1 { class: "text-4xl font-bold text-brand-orange mb-4", "No patient {id}" }
+                    p { class: "text-zinc-400", "Patients are numbered 1 to {count}." }
+                    Link { to: Route::Results {}, class: "mt-8 inline-block px-6 py-2 bg-brand-orange text-brand-dark rounded-full font-bold", "Back to Results" }
+                }
+            }
+        };
+    };
+    let records: Vec<BpRecord> = data
+        .bp
+        .records
+        .iter()
+        .filter(|r| r.mrn == mrn)
+        .cloned()
+        .collect();
+    let timeline = Timeline::build(&records);
+    let meq = data.meq.records.iter().find(|r| r.mrn == mrn);
+    let cgi = data
+        .cgi
+        .records
+        .iter()
+        .find(|r| r.mrn == mrn)
+        .map(|r| (r.score, cgi::label(r.score).replace('\n', " ")));
+    let markers = vec![
+        (Marker::Circle, "Lying".to_string()),
+        (Marker::Square, "Sitting".to_string()),
+        (Marker::Triangle, "Standing".to_string()),
+    ];
+
+    rsx! {
+        document::Title { "Patient {id} · STANDUP II" }
+        div { class: "min-h-screen w-full bg-brand-dark text-brand-light p-8 flex flex-col gap-6",
+            div { class: "flex justify-between items-end",
+                div {
+                    h1 { class: "text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-brand-orange to-orange-400 mb-2",
+                        "Patient {id}"
+                    }
+                    p { class: "text-sm font-mono text-brand-taupe",
+                        "{records.len()} readings in {timeline.sessions} sessions · patient {id} of {count}"
+                    }
+                }
+                div { class: "flex items-center gap-3",
+                    if !jump_digits().is_empty() {
+                        span { class: "text-sm font-mono text-brand-orange", "Go to patient {jump_digits}" }
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-full border border-white/10 text-sm text-brand-taupe hover:text-brand-light disabled:opacity-30 cursor-pointer",
+                        disabled: id <= 1,
+                        onclick: move |_| go(id - 1),
+                        "← Previous"
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-full border border-white/10 text-sm text-brand-taupe hover:text-brand-light disabled:opacity-30 cursor-pointer",
+                        disabled: id >= count,
+                        onclick: move |_| go(id + 1),
+                        "Next →"
+                    }
+                    Link { to: Route::Results {}, class: "px-4 py-2 rounded-full bg-brand-orange text-brand-dark text-sm font-bold", "Back to Results" }
+                }
+            }
+            div { class: "grid grid-cols-12 gap-8",
+                div { class: "col-span-8 flex flex-col gap-6",
+                    TimelineChart {
+                        title: "Blood Pressure",
+                        unit: "mmHg",
+                        series: vec![
+                            TimelineSeries { name: "Systolic".to_string(), points: timeline.systolic, color: theme::ORANGE },
+                            TimelineSeries { name: "Diastolic".to_string(), points: timeline.diastolic, color: theme::LIGHT },
+                        ],
+                        x_labels: timeline.labels.clone(),
+                        bands: timeline.bands.clone(),
+                        markers: markers.clone(),
+                    }
+                    TimelineChart {
+                        title: "Heart Rate",
+                        unit: "bpm",
+                        series: vec![TimelineSeries { name: "Heart rate".to_string(), points: timeline.heart_rate, color: theme::TAUPE }],
+                        x_labels: timeline.labels,
+                        bands: timeline.bands,
+                        markers,
+                    }
+                }
+                div { class: "col-span-4 flex flex-col gap-6",
+                    div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 space-y-4",
+                        h3 { class: "text-lg font-bold text-brand-orange uppercase", "Medication (MEQ)" }
+                        if let Some(meq) = meq {
+                            div { class: "flex justify-between",
+                                div { class: "flex flex-col gap-1",
+                                    span { class: "text-xs text-brand-taupe uppercase", "Pre-Intervention" }
+                                    span { class: "text-3xl font-mono font-bold text-brand-light", "{meq.pre:.1} ME" }
+                                }
+                                div { class: "flex flex-col gap-1 text-right",
+                                    span { class: "text-xs text-brand-taupe uppercase", "Post-Intervention" }
+                                    span { class: "text-3xl font-mono font-bold text-brand-light", "{meq.post:.1} ME" }
+                                }
+                            }
+                            div { class: "text-sm text-brand-taupe", "{MeqResponse::classify(meq).label()}" }
+                        } else {
+                            div { class: "text-sm text-brand-taupe", "No MEQ recorded." }
+                        }
+                    }
+                    div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 space-y-4",
+                        h3 { class: "text-lg font-bold text-brand-orange uppercase", "CGI-I" }
+                        if let Some((score, label)) = cgi {
+                            div { class: "text-5xl font-mono font-bold text-brand-light", "{score}" }
+                            div { class: "text-sm text-brand-taupe", "{label}" }
+                        } else {
+                            div { class: "text-sm text-brand-taupe", "Not rated." }
+                        }
+                    }
+                    p { class: "text-xs text-brand-taupe",
+                        "← / → previous or next patient · Home / End first or last · number + Enter to jump"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn reading(time: &str, pos: Position, sys: Option<i32>, phase: Phase) -> BpRecord {
+        BpRecord {
+            mrn: "SU001".to_string(),
+            time: NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap(),
+            sys,
+            dia: Some(70),
+            hr: Some(80),
+            pos,
+            phase,
+        }
+    }
+
+    fn xs(points: &[TimelinePoint]) -> Vec<f64> {
+        points
+            .iter()
+            .map(|p| (p.x * 1000.0).round() / 1000.0)
+            .collect()
+    }
+
+    /// Two stand tests an hour apart before the procedure, then a single
+    /// reading after it.
+    fn timeline() -> Timeline {
+        Timeline::build(&[
+            reading("2025-03-01 09:00", Position::Lying, Some(140), Phase::Pre),
+            reading("2025-03-01 09:05", Position::Standing, None, Phase::Pre),
+            reading(
+                "2025-03-01 09:10",
+                Position::Standing,
+                Some(110),
+                Phase::Pre,
+            ),
+            reading("2025-03-01 10:10", Position::Lying, Some(135), Phase::Pre),
+            reading(
+                "2025-03-01 10:14",
+                Position::Standing,
+                Some(120),
+                Phase::Pre,
+            ),
+            reading(
+                "2025-06-01 09:00",
+                Position::Sitting,
+                Some(130),
+                Phase::Post,
+            ),
+        ])
+    }
+
+    #[test]
+    fn readings_spread_across_their_session_slot() {
+        let timeline = timeline();
+        assert_eq!(timeline.sessions, 3);
+        // Each session's readings span the middle 70% of [i, i + 1), by time.
+        assert_eq!(xs(&timeline.heart_rate), [0.15, 0.5, 0.85, 1.15, 1.85, 2.5]);
+        let runs: Vec<usize> = timeline.heart_rate.iter().map(|p| p.run).collect();
+        assert_eq!(runs, [0, 0, 0, 1, 1, 2]);
+        // A blank value leaves a gap without moving the others.
+        assert_eq!(xs(&timeline.systolic), [0.15, 0.85, 1.15, 1.85, 2.5]);
+        assert_eq!(timeline.systolic[1].marker, Marker::Triangle);
+    }
+
+    #[test]
+    fn a_session_of_one_reading_sits_mid_slot() {
+        let timeline = timeline();
+        let last = timeline.diastolic.last().unwrap();
+        assert_eq!((last.x, last.marker), (2.5, Marker::Square));
+        let labels: Vec<_> = timeline
+            .labels
+            .iter()
+            .map(|(x, l)| (*x, l.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            [(0.5, "01 Mar 25"), (1.5, "01 Mar 25"), (2.5, "01 Jun 25")]
+        );
+    }
+
+    #[test]
+    fn bands_split_at_the_procedure() {
+        let bands: Vec<_> = timeline()
+            .bands
+            .iter()
+            .map(|b| (b.from, b.to, b.shaded))
+            .collect();
+        assert_eq!(bands, [(0.0, 2.0, false), (2.0, 3.0, true)]);
+
+        let post_only = Timeline::build(&[reading(
+            "2025-06-01 09:00",
+            Position::Lying,
+            Some(130),
+            Phase::Post,
+        )]);
+        assert_eq!(post_only.bands.len(), 1);
+        assert_eq!(post_only.bands[0].label, "Post-Intervention");
+        assert_eq!((post_only.bands[0].from, post_only.bands[0].to), (0.0, 1.0));
+        assert_eq!(Timeline::build(&[]), Timeline::default());
+    }
+}
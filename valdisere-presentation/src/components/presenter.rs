@@ -0,0 +1,254 @@
+use crate::components::deck_sync::{use_deck_channel, DeckMessage};
+use crate::components::fragments::{DeckPosition, FragmentScope};
+use crate::components::slide_deck::{use_deck_keys, DeckCommand};
+use crate::{render_slide, Route, SLIDES};
+use chrono::{DateTime, Local};
+use dioxus::prelude::*;
+
+/// Planned length of the Val d'Isère slot, used for the remaining-time readout.
+const TALK_MINUTES: i64 = 15;
+
+/// Slides are authored for a 16:9 canvas of this width and scaled down to
+/// fit the presenter previews.
+const CANVAS_WIDTH: f64 = 1600.0;
+
+/// Opens the presenter view in a second window. On the web this is a popup
+/// on `/presenter`; on desktop it is a new webview with its own router.
+pub fn open_presenter_window() {
+    #[cfg(not(feature = "desktop"))]
+    if let Some(window) = web_sys::window() {
+        let _ = window.open_with_url_and_target_and_features(
+            &Route::Presenter {}.to_string(),
+            "standup-presenter",
+            "popup,width=1400,height=850",
+        );
+    }
+
+    #[cfg(feature = "desktop")]
+    {
+        use dioxus::desktop::{Config, WindowBuilder};
+        let cfg = Config::new().with_window(WindowBuilder::new().with_title("STANDUP Presenter"));
+        drop(dioxus::desktop::window().new_window(VirtualDom::new(PresenterWindow), cfg));
+    }
+}
+
+/// Root of the desktop presenter window: the same router, started on
+/// `/presenter` instead of the first slide, with its own copy of the live
+/// data kept in step with the audience window's.
+#[cfg(feature = "desktop")]
+fn PresenterWindow() -> Element {
+    use dioxus::history::{History, MemoryHistory};
+    use std::rc::Rc;
+
+    crate::components::live_data::use_live_data_provider();
+    crate::components::live_data::use_live_data_sync();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: crate::TAILWIND_CSS }
+        HistoryProvider {
+            history: move |_| Rc::new(MemoryHistory::with_initial_path(Route::Presenter {})) as Rc<dyn History>,
+            Router::<Route> {}
+        }
+    }
+}
+
+#[cfg(not(feature = "desktop"))]
+async fn sleep_ms(ms: u32) {
+    gloo_timers::future::TimeoutFuture::new(ms).await;
+}
+
+#[cfg(feature = "desktop")]
+async fn sleep_ms(ms: u32) {
+    tokio::time::sleep(std::time::Duration::from_millis(ms.into())).await;
+}
+
+fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    format!("{sign}{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Colour of the remaining-time readout: amber in the last two minutes, red
+/// once over time.
+fn remaining_class(remaining: i64) -> &'static str {
+    if remaining < 0 {
+        "text-red-400"
+    } else if remaining < 120 {
+        "text-amber-400"
+    } else {
+        "text-brand-light"
+    }
+}
+
+#[component]
+pub fn Presenter() -> Element {
+    let mut current = use_signal(DeckPosition::default);
+    let mut audience_blackout = use_signal(|| false);
+    let mut started = use_signal(Local::now);
+    let mut now = use_signal(Local::now);
+
+    // Follow the audience window, and ask it where it is once we are open.
+    let channel = use_deck_channel(move |msg| match msg {
+        DeckMessage::Goto(position) => current.set(position),
+        DeckMessage::Blackout => audience_blackout.toggle(),
+        // Data files are applied by the window's live-data sync.
+        DeckMessage::Hello | DeckMessage::DataFile { .. } | DeckMessage::DataReset => {}
+    });
+    let channel_for_hello = channel.clone();
+    use_hook(move || {
+        if let Some(channel) = &channel_for_hello {
+            channel.post(DeckMessage::Hello);
+        }
+    });
+
+    let jump_digits = use_deck_keys(move |command| match command {
+        DeckCommand::OpenPresenter => {}
+        DeckCommand::Blackout => {
+            audience_blackout.toggle();
+            if let Some(channel) = &channel {
+                channel.post(DeckMessage::Blackout);
+            }
+        }
+        _ => {
+            let from = *current.peek();
+            if let Some(target) = command.target(from) {
+                current.set(target);
+                if let Some(channel) = &channel {
+                    channel.post(DeckMessage::Goto(target));
+                }
+            }
+        }
+    });
+
+    use_future(move || async move {
+        loop {
+            sleep_ms(1000).await;
+            now.set(Local::now());
+        }
+    });
+
+    let position = current();
+    let index = position.slide.min(SLIDES.len() - 1);
+    let slide = &SLIDES[index];
+    // The next preview shows whatever Next will reveal: another fragment of
+    // this slide, or the next slide.
+    let next = position.next();
+    let (step, steps) = position.progress();
+    let started_at: DateTime<Local> = started();
+    let elapsed = (now() - started_at).num_seconds().max(0);
+    let remaining = TALK_MINUTES * 60 - elapsed;
+    let clock = now().format("%H:%M").to_string();
+
+    rsx! {
+        div { class: "h-screen w-full bg-zinc-950 text-slate-200 font-sans flex flex-col p-6 gap-6 overflow-hidden",
+
+            // Status bar
+            div { class: "flex items-center justify-between",
+                div { class: "flex items-baseline gap-4",
+                    span { class: "font-black text-xl tracking-tighter text-brand-orange", "Presenter" }
+                    span { class: "text-zinc-400 font-mono", "{index + 1} / {SLIDES.len()}" }
+                    span { class: "text-brand-light font-semibold", "{slide.title}" }
+                    if steps > 0 {
+                        span { class: "text-zinc-400 font-mono text-sm", "step {step} / {steps}" }
+                    }
+                    if audience_blackout() {
+                        span { class: "px-2 py-1 rounded bg-zinc-800 text-xs font-bold uppercase text-zinc-300", "Audience blacked out" }
+                    }
+                    if !jump_digits.read().is_empty() {
+                        span { class: "px-2 py-1 rounded border border-brand-orange text-brand-orange font-mono text-sm", "Go to {jump_digits}" }
+                    }
+                }
+                div { class: "flex items-center gap-8 font-mono",
+                    TimerReadout { label: "Elapsed", value: format_duration(elapsed), class: "text-brand-light" }
+                    TimerReadout { label: "Remaining", value: format_duration(remaining), class: remaining_class(remaining) }
+                    TimerReadout { label: "Clock", value: clock, class: "text-brand-taupe" }
+                    button {
+                        class: "px-4 py-2 rounded-full text-sm font-bold border border-white/10 text-brand-taupe hover:border-brand-orange/50 hover:text-brand-light cursor-pointer",
+                        onclick: move |_| {
+                            started.set(Local::now());
+                            now.set(Local::now());
+                        },
+                        "Reset timer"
+                    }
+                }
+            }
+
+            div { class: "flex-1 grid grid-cols-12 gap-6 min-h-0",
+                // Current slide
+                div { class: "col-span-7 flex flex-col gap-2",
+                    span { class: "text-xs uppercase tracking-widest text-zinc-500", "Current" }
+                    SlidePreview { position: DeckPosition { slide: index, ..position }, width: 960.0 }
+                }
+
+                // Next slide and notes
+                div { class: "col-span-5 flex flex-col gap-4 min-h-0",
+                    span { class: "text-xs uppercase tracking-widest text-zinc-500", "Next" }
+                    if let Some(next) = next {
+                        SlidePreview { position: next, width: 560.0 }
+                    } else {
+                        div { class: "h-[315px] w-[560px] rounded-xl border border-white/10 flex items-center justify-center text-zinc-500", "End of deck" }
+                    }
+                    span { class: "text-xs uppercase tracking-widest text-zinc-500", "Notes" }
+                    div { class: "flex-1 overflow-y-auto p-4 rounded-xl bg-zinc-900/60 border border-white/10 text-2xl leading-relaxed text-brand-light",
+                        "{slide.notes}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TimerReadout(label: &'static str, value: String, class: &'static str) -> Element {
+    rsx! {
+        div { class: "flex flex-col items-end",
+            span { class: "text-[10px] uppercase tracking-widest text-zinc-500", "{label}" }
+            span { class: "text-2xl font-bold {class}", "{value}" }
+        }
+    }
+}
+
+/// A slide rendered at full canvas size and scaled down to `width` pixels,
+/// with its fragments revealed up to the position's step. Pointer events are
+/// disabled so previews cannot be clicked into.
+#[component]
+fn SlidePreview(position: DeckPosition, width: f64) -> Element {
+    let height = width * 9.0 / 16.0;
+    let scale = width / CANVAS_WIDTH;
+
+    rsx! {
+        div {
+            class: "relative overflow-hidden rounded-xl border border-white/10 bg-brand-dark shrink-0",
+            style: "width: {width}px; height: {height}px;",
+            div {
+                class: "absolute top-0 left-0 origin-top-left pointer-events-none flex flex-col",
+                style: "width: {CANVAS_WIDTH}px; height: {CANVAS_WIDTH * 9.0 / 16.0}px; transform: scale({scale});",
+                FragmentScope { slide: position.slide, step: position.step,
+                    {render_slide(&SLIDES[position.slide].route)}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_are_minutes_and_seconds() {
+        assert_eq!(format_duration(0), "00:00");
+        assert_eq!(format_duration(125), "02:05");
+        assert_eq!(format_duration(TALK_MINUTES * 60), "15:00");
+        assert_eq!(format_duration(-30), "-00:30");
+        assert_eq!(format_duration(-61), "-01:01");
+    }
+
+    #[test]
+    fn remaining_time_warns_then_overruns() {
+        assert_eq!(remaining_class(120), "text-brand-light");
+        assert_eq!(remaining_class(119), "text-amber-400");
+        assert_eq!(remaining_class(0), "text-amber-400");
+        assert_eq!(remaining_class(-1), "text-red-400");
+    }
+}
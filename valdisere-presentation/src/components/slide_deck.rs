@@ -1,146 +1,328 @@
-use crate::Route;
+use crate::components::deck_sync::{use_deck_channel, DeckMessage};
+use crate::components::fragments::DeckPosition;
+use crate::components::presenter;
+use crate::{registry, Route, SLIDES};
 use dioxus::prelude::*;
-use gloo_events::EventListener;
 use std::cell::RefCell;
 use std::rc::Rc;
-use wasm_bindgen::JsCast;
-use web_sys::KeyboardEvent;
-
-const SLIDE_ORDER: &[&str] = &[
-    "/",
-    "/motivation",
-    "/background",
-    "/rationale",
-    "/methods",
-    "/results",
-    "/discussion",
-    "/future",
-    "/video",
-];
-
-fn normalize_path(path: &str) -> String {
-    let path = path.trim();
-    if path == "/" {
-        return "/".to_string();
-    }
-    let path = if path.ends_with('/') {
-        &path[..path.len() - 1]
-    } else {
-        path
-    };
-    if !path.starts_with('/') {
-        format!("/{}", path)
-    } else {
-        path.to_string()
+
+/// What a key press asks the deck to do. Presentation clickers send
+/// PageUp/PageDown (and often `b` or `.` for their blank-screen button), so
+/// those are mapped alongside the arrow keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyAction {
+    Next,
+    Prev,
+    First,
+    Last,
+    Blackout,
+    Presenter,
+    Digit(char),
+    Jump,
+    ClearJump,
+}
+
+/// The action for `key`. Enter only jumps while digits are pending; on its
+/// own it is left to the page.
+fn key_action(key: &str, jump_pending: bool) -> Option<KeyAction> {
+    match key {
+        "ArrowRight" | "PageDown" | " " => Some(KeyAction::Next),
+        "ArrowLeft" | "PageUp" => Some(KeyAction::Prev),
+        "Home" => Some(KeyAction::First),
+        "End" => Some(KeyAction::Last),
+        "b" | "B" | "." => Some(KeyAction::Blackout),
+        "p" | "P" => Some(KeyAction::Presenter),
+        "Enter" if jump_pending => Some(KeyAction::Jump),
+        "Escape" => Some(KeyAction::ClearJump),
+        _ if key.len() == 1 && key.chars().all(|c| c.is_ascii_digit()) => {
+            key.chars().next().map(KeyAction::Digit)
+        }
+        _ => None,
     }
 }
 
-pub fn get_next_route(current_path: &str) -> Option<&'static str> {
-    let normalized = normalize_path(current_path);
-    if let Some(pos) = SLIDE_ORDER.iter().position(|&r| r == normalized) {
-        if pos + 1 < SLIDE_ORDER.len() {
-            return Some(SLIDE_ORDER[pos + 1]);
+/// Elements that handle keys themselves, such as form fields or the
+/// controls of a `<video>`. The deck ignores every key aimed at them.
+const KEY_TARGETS: &str = "input, textarea, select, video, audio, [contenteditable]";
+
+/// Controls activated by Enter and Space. The deck leaves those two keys to
+/// them, so a focused link or button still works from the keyboard.
+const CONTROL_TARGETS: &str = "button, a[href], [role=button]";
+
+fn activates_control(key: &str) -> bool {
+    matches!(key, "Enter" | " ")
+}
+
+/// A resolved deck command, after number+Enter input has been collected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeckCommand {
+    Next,
+    Prev,
+    First,
+    Last,
+    /// Jump to a slide by index into `SLIDES`.
+    Goto(usize),
+    Blackout,
+    OpenPresenter,
+}
+
+impl DeckCommand {
+    /// Where this command moves the deck from `current`, if it navigates.
+    /// Next and Prev step through fragments before changing slide.
+    pub fn target(self, current: DeckPosition) -> Option<DeckPosition> {
+        let last = SLIDES.len() - 1;
+        match self {
+            DeckCommand::Next => current.next(),
+            DeckCommand::Prev => current.prev(),
+            DeckCommand::First => Some(DeckPosition::start_of(0)),
+            DeckCommand::Last => Some(DeckPosition::start_of(last)),
+            DeckCommand::Goto(i) => (i <= last).then_some(DeckPosition::start_of(i)),
+            DeckCommand::Blackout | DeckCommand::OpenPresenter => None,
         }
     }
-    None
 }
 
-pub fn get_prev_route(current_path: &str) -> Option<&'static str> {
-    let normalized = normalize_path(current_path);
-    if let Some(pos) = SLIDE_ORDER.iter().position(|&r| r == normalized) {
-        if pos > 0 {
-            return Some(SLIDE_ORDER[pos - 1]);
+/// Installs the deck keymap for the lifetime of the calling component and
+/// returns the digits typed so far for a number+Enter jump.
+///
+/// Key events arrive outside of Dioxus, so handling is wrapped in a
+/// `Callback` to run it inside this component's runtime.
+pub fn use_deck_keys(on_command: impl FnMut(DeckCommand) + 'static) -> Signal<String> {
+    let mut jump_digits = use_signal(String::new);
+    let mut on_command = on_command;
+
+    let handle_key = use_callback(move |key: String| -> bool {
+        let Some(action) = key_action(&key, !jump_digits.read().is_empty()) else {
+            return false;
+        };
+        let command = match action {
+            KeyAction::Next => Some(DeckCommand::Next),
+            KeyAction::Prev => Some(DeckCommand::Prev),
+            KeyAction::First => Some(DeckCommand::First),
+            KeyAction::Last => Some(DeckCommand::Last),
+            KeyAction::Blackout => Some(DeckCommand::Blackout),
+            KeyAction::Presenter => Some(DeckCommand::OpenPresenter),
+            KeyAction::Digit(d) => {
+                jump_digits.write().push(d);
+                None
+            }
+            // Slides are numbered from 1 on screen.
+            KeyAction::Jump => jump_digits
+                .replace(String::new())
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .map(DeckCommand::Goto),
+            KeyAction::ClearJump => {
+                jump_digits.set(String::new());
+                None
+            }
+        };
+        if let Some(command) = command {
+            on_command(command);
         }
+        true
+    });
+
+    keys::use_key_listener(move |key| handle_key.call(key.to_string()));
+    jump_digits
+}
+
+#[cfg(not(feature = "desktop"))]
+mod keys {
+    use dioxus::prelude::*;
+    use gloo_events::{EventListener, EventListenerOptions};
+    use std::rc::Rc;
+    use wasm_bindgen::JsCast;
+    use web_sys::KeyboardEvent;
+
+    /// True when the key press comes from inside an element matching `selector`.
+    fn targets(event: &KeyboardEvent, selector: &str) -> bool {
+        event
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .and_then(|el| el.closest(selector).ok().flatten())
+            .is_some()
+    }
+
+    pub fn use_key_listener(mut handle_key: impl FnMut(&str) -> bool + 'static) {
+        // Create the EventListener ONCE and keep it alive for the component's lifetime.
+        // use_hook stores the return value.
+        use_hook(move || {
+            let window = web_sys::window().unwrap();
+            // Non-passive so Space and PageUp/PageDown can be kept from scrolling the slide.
+            let options = EventListenerOptions::enable_prevent_default();
+            let listener =
+                EventListener::new_with_options(&window, "keydown", options, move |event| {
+                    let event = event.dyn_ref::<KeyboardEvent>().unwrap();
+                    // Leave shortcuts alone. Shift is not one: it gives "B", and the
+                    // digits on an AZERTY keyboard.
+                    if event.alt_key() || event.ctrl_key() || event.meta_key() {
+                        return;
+                    }
+                    let key = event.key();
+                    if targets(event, super::KEY_TARGETS)
+                        || (super::activates_control(&key)
+                            && targets(event, super::CONTROL_TARGETS))
+                    {
+                        return;
+                    }
+                    if handle_key(&key) {
+                        event.prevent_default();
+                    }
+                });
+            Rc::new(listener)
+        });
+    }
+}
+
+#[cfg(feature = "desktop")]
+mod keys {
+    use dioxus::prelude::*;
+
+    // The desktop webview cannot be reached through web-sys, so the same
+    // filtering runs as a script that forwards accepted keys back to Rust.
+    fn key_script() -> String {
+        format!(
+            r#"
+            window.addEventListener("keydown", (e) => {{
+                if (e.altKey || e.ctrlKey || e.metaKey) return;
+                const within = (selector) => e.target.closest && e.target.closest(selector);
+                if (within("{}")) return;
+                if (["Enter", " "].includes(e.key) && within("{}")) return;
+                if ([" ", "PageUp", "PageDown"].includes(e.key)) e.preventDefault();
+                dioxus.send(e.key);
+            }});
+            "#,
+            super::KEY_TARGETS,
+            super::CONTROL_TARGETS,
+        )
+    }
+
+    pub fn use_key_listener(mut handle_key: impl FnMut(&str) -> bool + 'static) {
+        use_hook(move || {
+            let mut eval = document::eval(&key_script());
+            spawn(async move {
+                while let Ok(key) = eval.recv::<String>().await {
+                    handle_key(&key);
+                }
+            })
+        });
     }
-    None
 }
 
 #[component]
 pub fn NavControls() -> Element {
     let nav = use_navigator();
     let route = use_route::<Route>();
+    let mut position = use_context::<Signal<DeckPosition>>();
+    let mut blackout = use_signal(|| false);
+    // Bumped when a presenter window says hello, so our position is re-announced.
+    let mut announce = use_signal(|| 0u32);
 
-    // 1. We use a raw Rc<RefCell> to share state with the event listener safely.
+    // 1. We use a raw Rc<RefCell> to share the current route with the listeners.
     // This avoids Dioxus Signal runtime borrowing conflicts.
     // Rc is Clone, so use_hook is happy.
-    let nav_state = use_hook(|| Rc::new(RefCell::new((None::<String>, None::<String>))));
-
-    // 2. Calculate routes efficiently on every render
-    let current_path = route.to_string();
-    let next_str = get_next_route(&current_path).map(|s| s.to_string());
-    let prev_str = get_prev_route(&current_path).map(|s| s.to_string());
-
-    // 3. Update the shared state using use_effect to avoid RefCell borrow failures during render.
-    // We clone the values to move them into the effect closure.
-    let nav_state_update = nav_state.clone();
-    let p_clone = prev_str.clone();
-    let n_clone = next_str.clone();
-
-    use_effect(move || {
-        if let Ok(mut state) = nav_state_update.try_borrow_mut() {
-            *state = (p_clone.clone(), n_clone.clone());
-        } else {
-            web_sys::console::warn_1(&"NavControls: Failed to borrow nav_state for update".into());
+    let current = use_hook(|| Rc::new(RefCell::new(route.clone())));
+
+    // 2. Work out where we are. The stored position only counts while its slide
+    // is on screen; arriving any other way (navbar, back button) starts fresh.
+    let index = registry::slide_index(&route);
+    let here = index.map(|i| position().on(i));
+    let can_go_back = here.and_then(|p| p.prev()).is_some();
+    let can_go_forward = here.and_then(|p| p.next()).is_some();
+
+    // 3. Keep the shared route in step with the router. NavControls lives in the
+    // layout, so it re-renders on every navigation without being remounted.
+    if let Ok(mut state) = current.try_borrow_mut() {
+        *state = route.clone();
+    } else {
+        warn!("NavControls: Failed to borrow current route for update");
+    }
+
+    // 4. Moving to a position updates the fragment step and, when the slide
+    // changes, the route. Clone out of the RefCell so the borrow is released
+    // before navigating, which re-renders and updates the shared route.
+    let current_for_move = current.clone();
+    let move_to = use_callback(move |target: DeckPosition| {
+        let Some(slide) = SLIDES.get(target.slide) else {
+            return;
+        };
+        let on_slide = current_for_move
+            .try_borrow()
+            .ok()
+            .and_then(|r| registry::slide_index(&r));
+        position.set(target);
+        if on_slide != Some(target.slide) {
+            debug!("Deck Nav -> {}", slide.route);
+            nav.push(slide.route.clone());
         }
     });
 
-    // 4. Create the EventListener ONCE and keep it alive in a use_signal (or just use_hook resource).
-    // The previous implementation used use_signal to hold the listener, which is fine.
-    // We clone the Rc for the closure.
-    let nav_state_for_listener = nav_state.clone();
-    let nav_for_listener = nav.clone();
-
-    // Store listener in a signal to keep it alive for the component's lifetime
-    use_hook(move || {
-        let window = web_sys::window().unwrap();
-
-        let listener = EventListener::new(&window, "keydown", move |event| {
-            let event = event.dyn_ref::<KeyboardEvent>().unwrap();
-            let key = event.key();
-            // Ignore modifiers
-            if event.alt_key() || event.ctrl_key() || event.meta_key() || event.shift_key() {
-                return;
-            }
+    // 5. Follow the presenter window.
+    let channel = use_deck_channel(move |msg| match msg {
+        DeckMessage::Goto(target) => move_to(target),
+        DeckMessage::Blackout => blackout.toggle(),
+        DeckMessage::Hello => *announce.write() += 1,
+        // Data files are applied by the window's live-data sync.
+        DeckMessage::DataFile { .. } | DeckMessage::DataReset => {}
+    });
 
-            match key.as_str() {
-                "ArrowRight" | " " => {
-                    // Safe borrow from RefCell
-                    if let Ok(state) = nav_state_for_listener.try_borrow() {
-                        let target = state.1.clone();
-                        if let Some(t) = target {
-                            web_sys::console::log_1(&format!("Key Nav -> {}", t).into());
-                            nav_for_listener.push(t);
-                        }
-                    }
-                }
-                "ArrowLeft" => {
-                    if let Ok(state) = nav_state_for_listener.try_borrow() {
-                        let target = state.0.clone();
-                        if let Some(t) = target {
-                            web_sys::console::log_1(&format!("Key Nav -> {}", t).into());
-                            nav_for_listener.push(t);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        });
+    let channel_for_effect = channel.clone();
+    use_effect(use_reactive!(|index| {
+        announce.read();
+        let here = index.map(|i| position().on(i));
+        if let (Some(channel), Some(here)) = (&channel_for_effect, here) {
+            channel.post(DeckMessage::Goto(here));
+        }
+    }));
 
-        // Return boxed listener or just the listener.
-        // use_hook stores the return value.
-        // We need to keep this object alive.
-        Rc::new(listener)
+    // 6. Keyboard and clicker navigation.
+    let current_for_keys = current.clone();
+    let jump_digits = use_deck_keys(move |command| {
+        if command == DeckCommand::OpenPresenter {
+            presenter::open_presenter_window();
+            return;
+        }
+        if command == DeckCommand::Blackout {
+            blackout.toggle();
+            if let Some(channel) = &channel {
+                channel.post(DeckMessage::Blackout);
+            }
+            return;
+        }
+        let Some(index) = current_for_keys
+            .try_borrow()
+            .ok()
+            .and_then(|r| registry::slide_index(&r))
+        else {
+            return;
+        };
+        if let Some(target) = command.target(position.peek().on(index)) {
+            blackout.set(false);
+            move_to(target);
+        }
     });
 
     rsx! {
+        if blackout() {
+            div { class: "fixed inset-0 bg-black z-[100] cursor-none" }
+        }
+        if !jump_digits.read().is_empty() {
+            div { class: "fixed bottom-8 left-8 z-50 px-4 py-2 rounded-lg bg-brand-dark border border-brand-orange text-brand-orange font-mono text-lg",
+                "Go to slide {jump_digits} / {SLIDES.len()}"
+            }
+        }
         div {
             class: "fixed bottom-8 right-8 flex gap-4 z-50",
 
-            if let Some(p) = prev_str {
+            if can_go_back {
                 button {
                     class: "p-3 rounded-full bg-brand-dark hover:bg-zinc-700 text-brand-orange shadow-lg border border-brand-orange transition-all active:scale-95 cursor-pointer opacity-100",
                     onclick: move |_| {
-                        nav.push(p.clone());
+                        if let Some(target) = here.and_then(|p| p.prev()) {
+                            move_to(target);
+                        }
                     },
                     svg {
                         class: "w-6 h-6",
@@ -154,11 +336,13 @@ pub fn NavControls() -> Element {
                     }
                 }
             }
-            if let Some(n) = next_str {
+            if can_go_forward {
                 button {
                     class: "p-3 rounded-full bg-brand-dark hover:bg-zinc-700 text-brand-orange shadow-lg border border-brand-orange transition-all active:scale-95 cursor-pointer opacity-100",
                     onclick: move |_| {
-                        nav.push(n.clone());
+                        if let Some(target) = here.and_then(|p| p.next()) {
+                            move_to(target);
+                        }
                     },
                     svg {
                         class: "w-6 h-6",
@@ -175,3 +359,31 @@ pub fn NavControls() -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifted_keys_map_like_unshifted() {
+        assert_eq!(key_action("B", false), Some(KeyAction::Blackout));
+        assert_eq!(key_action("P", false), Some(KeyAction::Presenter));
+        // Shift+& on an AZERTY keyboard.
+        assert_eq!(key_action("1", false), Some(KeyAction::Digit('1')));
+        assert_eq!(key_action("&", false), None);
+    }
+
+    #[test]
+    fn enter_is_only_taken_to_finish_a_jump() {
+        assert_eq!(key_action("Enter", false), None);
+        assert_eq!(key_action("Enter", true), Some(KeyAction::Jump));
+    }
+
+    #[test]
+    fn controls_keep_enter_and_space() {
+        assert!(activates_control("Enter"));
+        assert!(activates_control(" "));
+        assert!(!activates_control("ArrowRight"));
+        assert!(!activates_control("PageDown"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Clinical Global Impression – Improvement (CGI-I): the clinician's rating
+//! of how much the patient changed after the procedure, from 1 (very much
+//! improved) to 7 (very much worse).
+//!
+//! The scale is ordinal, so it is summarised by counts, the median and the
+//! interquartile range rather than a mean. A patient rated 1 or 2 counts as
+//! a responder, the usual cut-off in trials.
+
+use crate::data::records::CgiRecord;
+use crate::data::stats::{clopper_pearson, median, quantile, Interval};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Every score on the scale, shown whether or not anyone received it.
+pub const SCORES: RangeInclusive<i32> = 1..=7;
+/// Scores up to this one count as response: much or very much improved.
+pub const RESPONDER_MAX: i32 = 2;
+
+/// Short anchor for a score, split over two lines for chart axes.
+pub fn label(score: i32) -> &'static str {
+    match score {
+        1 => "Very much\nimproved",
+        2 => "Much\nimproved",
+        3 => "Minimally\nimproved",
+        4 => "No change",
+        5 => "Minimally\nworse",
+        6 => "Much\nworse",
+        _ => "Very much\nworse",
+    }
+}
+
+pub fn is_responder(score: i32) -> bool {
+    (1..=RESPONDER_MAX).contains(&score)
+}
+
+/// `k` of `n` patients, with the exact 95% CI of the rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Proportion {
+    pub k: usize,
+    pub n: usize,
+    pub ci: Interval,
+}
+
+impl Proportion {
+    pub fn of(k: usize, n: usize) -> Option<Self> {
+        Some(Self {
+            k,
+            n,
+            ci: clopper_pearson(k, n)?,
+        })
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.k as f64 / self.n as f64
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CgiSummary {
+    /// Patients per score, in the order of [`SCORES`].
+    pub counts: Vec<usize>,
+    pub responders: Option<Proportion>,
+    pub median: Option<f64>,
+    /// Lower and upper quartile.
+    pub iqr: Option<(f64, f64)>,
+}
+
+impl CgiSummary {
+    pub fn build(records: &[CgiRecord]) -> Self {
+        let scores: Vec<f64> = records.iter().map(|r| f64::from(r.score)).collect();
+        let responders = records.iter().filter(|r| is_responder(r.score)).count();
+        Self {
+            counts: SCORES
+                .map(|score| records.iter().filter(|r| r.score == score).count())
+                .collect(),
+            responders: Proportion::of(responders, records.len()),
+            median: median(&scores),
+            iqr: quantile(&scores, 0.25).zip(quantile(&scores, 0.75)),
+        }
+    }
+}
+
+/// CGI-I response against another yes/no outcome, for the patients who
+/// have both.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CrossTab {
+    /// The other outcome, e.g. "MEQ ≥50% reduction".
+    pub outcome: String,
+    /// `[CGI-I responder, non-responder]`, each `[outcome, no outcome]`.
+    pub counts: [[usize; 2]; 2],
+}
+
+impl CrossTab {
+    /// `outcomes` maps each patient to whether they had the other outcome.
+    pub fn build(records: &[CgiRecord], outcome: &str, outcomes: &BTreeMap<&str, bool>) -> Self {
+        let mut counts = [[0; 2]; 2];
+        for r in records {
+            if let Some(&yes) = outcomes.get(r.mrn.as_str()) {
+                counts[usize::from(!is_responder(r.score))][usize::from(!yes)] += 1;
+            }
+        }
+        Self {
+            outcome: outcome.to_string(),
+            counts,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Patients on which both outcomes agree.
+    pub fn concordant(&self) -> usize {
+        self.counts[0][0] + self.counts[1][1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(scores: &[i32]) -> Vec<CgiRecord> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| CgiRecord {
+                mrn: format!("SU{:03}", i + 1),
+                score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn responders_are_much_or_very_much_improved() {
+        assert!(is_responder(1));
+        assert!(is_responder(2));
+        assert!(!is_responder(3));
+        assert!(!is_responder(0));
+    }
+
+    #[test]
+    fn summary_of_known_scores() {
+        let summary = CgiSummary::build(&scores(&[1, 2, 2, 3, 4, 4, 4, 6]));
+        assert_eq!(summary.counts, [1, 2, 1, 3, 0, 1, 0]);
+        assert_eq!(summary.median, Some(3.5));
+        assert_eq!(summary.iqr, Some((2.0, 4.0)));
+        let responders = summary.responders.unwrap();
+        assert_eq!((responders.k, responders.n), (3, 8));
+        assert_eq!(responders.rate(), 0.375);
+    }
+
+    #[test]
+    fn no_scores_no_summary() {
+        let summary = CgiSummary::build(&[]);
+        assert_eq!(summary.counts, [0; 7]);
+        assert_eq!(summary.responders, None);
+        assert_eq!(summary.median, None);
+        assert_eq!(summary.iqr, None);
+    }
+
+    #[test]
+    fn cross_tab_counts_only_patients_with_both() {
+        let records = scores(&[1, 2, 5, 4]);
+        let outcomes = BTreeMap::from([("SU001", true), ("SU002", false), ("SU003", false)]);
+        let tab = CrossTab::build(&records, "MEQ", &outcomes);
+        assert_eq!(tab.counts, [[1, 1], [0, 1]]);
+        assert_eq!(tab.total(), 3);
+        assert_eq!(tab.concordant(), 2);
+    }
+}
@@ -0,0 +1,152 @@
+//! The study cohort, joined across the BP, MEQ and CGI-I exports by MRN.
+//!
+//! Each export covers a different set of patients, so every number on the
+//! Results slide needs its own denominator. This module derives those
+//! denominators from the loaded records rather than typing them in.
+
+use crate::data::records::{BpRecord, CgiRecord, MeqRecord, Phase};
+use std::collections::BTreeMap;
+
+/// Short names for the fields of [`Inclusion`], in declaration order.
+pub const DATASETS: [&str; 4] = ["BP pre", "BP post", "MEQ", "CGI-I"];
+
+/// Which datasets a patient appears in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Inclusion {
+    pub bp_pre: bool,
+    pub bp_post: bool,
+    pub meq: bool,
+    pub cgi: bool,
+}
+
+impl Inclusion {
+    /// Has BP readings both before and after the procedure, which is what the
+    /// hemodynamic comparison needs.
+    pub fn bp_paired(&self) -> bool {
+        self.bp_pre && self.bp_post
+    }
+
+    pub fn bp_any(&self) -> bool {
+        self.bp_pre || self.bp_post
+    }
+
+    /// Membership in the order of [`DATASETS`].
+    pub fn flags(&self) -> [bool; 4] {
+        [self.bp_pre, self.bp_post, self.meq, self.cgi]
+    }
+
+    /// Short label for the combination, e.g. "BP + MEQ + CGI-I".
+    pub fn label(&self) -> String {
+        let bp = match (self.bp_pre, self.bp_post) {
+            (true, true) => Some("BP"),
+            (true, false) => Some("BP pre only"),
+            (false, true) => Some("BP post only"),
+            (false, false) => None,
+        };
+        let parts: Vec<&str> = [bp, self.meq.then_some("MEQ"), self.cgi.then_some("CGI-I")]
+            .into_iter()
+            .flatten()
+            .collect();
+        if parts.is_empty() {
+            "No data".to_string()
+        } else {
+            parts.join(" + ")
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Cohort {
+    /// Keyed by MRN; ordered so that patient listings are stable.
+    patients: BTreeMap<String, Inclusion>,
+}
+
+impl Cohort {
+    pub fn build(bp: &[BpRecord], meq: &[MeqRecord], cgi: &[CgiRecord]) -> Self {
+        let mut patients: BTreeMap<String, Inclusion> = BTreeMap::new();
+        for r in bp {
+            let entry = patients.entry(r.mrn.clone()).or_default();
+            match r.phase {
+                Phase::Pre => entry.bp_pre = true,
+                Phase::Post => entry.bp_post = true,
+            }
+        }
+        for r in meq {
+            patients.entry(r.mrn.clone()).or_default().meq = true;
+        }
+        for r in cgi {
+            patients.entry(r.mrn.clone()).or_default().cgi = true;
+        }
+        Self { patients }
+    }
+
+    /// Patients appearing in any dataset.
+    pub fn n_total(&self) -> usize {
+        self.patients.len()
+    }
+
+    fn count(&self, pred: impl Fn(&Inclusion) -> bool) -> usize {
+        self.patients.values().filter(|i| pred(i)).count()
+    }
+
+    /// Denominator for the hemodynamic comparison (paired pre/post BP).
+    pub fn n_bp(&self) -> usize {
+        self.count(Inclusion::bp_paired)
+    }
+
+    /// Patients with any BP reading, paired or not.
+    pub fn n_bp_any(&self) -> usize {
+        self.count(Inclusion::bp_any)
+    }
+
+    pub fn n_meq(&self) -> usize {
+        self.count(|i| i.meq)
+    }
+
+    pub fn n_cgi(&self) -> usize {
+        self.count(|i| i.cgi)
+    }
+
+    /// The study's patient number, as in the `Patient` column of the
+    /// summary exports: the 1-based rank by MRN among patients with paired
+    /// BP readings.
+    pub fn patient_number(&self, mrn: &str) -> Option<usize> {
+        if !self.patients.get(mrn)?.bp_paired() {
+            return None;
+        }
+        Some(
+            self.patients
+                .iter()
+                .take_while(|(m, _)| m.as_str() < mrn)
+                .filter(|(_, i)| i.bp_paired())
+                .count()
+                + 1,
+        )
+    }
+
+    /// The patient behind a study patient number; the inverse of
+    /// [`Cohort::patient_number`].
+    pub fn patient_mrn(&self, number: usize) -> Option<&str> {
+        self.patients
+            .iter()
+            .filter(|(_, i)| i.bp_paired())
+            .nth(number.checked_sub(1)?)
+            .map(|(mrn, _)| mrn.as_str())
+    }
+
+    /// Each patient's inclusion, in MRN order.
+    pub fn inclusions(&self) -> impl Iterator<Item = &Inclusion> {
+        self.patients.values()
+    }
+
+    /// Number of patients per combination of datasets, largest group first.
+    pub fn breakdown(&self) -> Vec<(Inclusion, usize)> {
+        let mut groups: BTreeMap<Inclusion, usize> = BTreeMap::new();
+        for inclusion in self.patients.values() {
+            *groups.entry(*inclusion).or_default() += 1;
+        }
+        let mut groups: Vec<(Inclusion, usize)> = groups.into_iter().collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.1));
+        groups
+    }
+}
@@ -0,0 +1,269 @@
+//! Checks the spreadsheet's per-patient summary against the raw readings.
+//!
+//! `standup_results_bp.csv` holds each patient's mean orthostatic systolic
+//! drop before and after the procedure, worked out in the spreadsheet as the
+//! mean over every stand test in the phase. Those numbers should follow from
+//! `standup_results.csv`, so they are recomputed the same way, with
+//! [`Aggregation::Pooled`], and compared patient by patient. The rule chosen
+//! on the Results slide does not apply: the check is of the spreadsheet's
+//! arithmetic, not of the slide. Patients found in only one of the two files
+//! are listed too.
+
+use crate::data::hemodynamics::{orthostatic_drops, DropStats};
+use crate::data::records::{BpRecord, Phase, SummaryRecord};
+use crate::data::visits::Aggregation;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Differences up to this many mmHg are rounding, not disagreement: the
+/// slides show one decimal place.
+pub const DEFAULT_TOLERANCE: f64 = 0.05;
+
+/// The summary columns that are recomputed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Pre,
+    Post,
+    Change,
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Measure::Pre => "mean pre drop",
+            Measure::Post => "mean post drop",
+            Measure::Change => "pre–post change",
+        })
+    }
+}
+
+/// A summary value that does not match the readings. Either side is `None`
+/// when it is missing: a blank summary cell, or no stand test in a phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub mrn: String,
+    /// The `Patient` number in the summary.
+    pub number: Option<usize>,
+    pub measure: Measure,
+    pub summary: Option<f64>,
+    pub recomputed: Option<f64>,
+}
+
+impl Discrepancy {
+    /// Recomputed minus summary, when both exist.
+    pub fn difference(&self) -> Option<f64> {
+        self.recomputed.zip(self.summary).map(|(r, s)| r - s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsistencyReport {
+    pub tolerance: f64,
+    /// Patients in both files.
+    pub checked: usize,
+    pub discrepancies: Vec<Discrepancy>,
+    /// In the summary but without a single reading.
+    pub only_in_summary: Vec<String>,
+    /// With readings but missing from the summary.
+    pub only_in_readings: Vec<String>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+            && self.only_in_summary.is_empty()
+            && self.only_in_readings.is_empty()
+    }
+
+    /// Patients with at least one discrepancy.
+    pub fn patients_differing(&self) -> usize {
+        self.discrepancies
+            .iter()
+            .map(|d| d.mrn.as_str())
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
+fn differs(summary: Option<f64>, recomputed: Option<f64>, tolerance: f64) -> bool {
+    match (summary, recomputed) {
+        (Some(s), Some(r)) => (s - r).abs() > tolerance,
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+/// Compares every summary row with the pooled drops recomputed from
+/// `readings`.
+pub fn check(
+    readings: &[BpRecord],
+    summary: &[SummaryRecord],
+    tolerance: f64,
+) -> ConsistencyReport {
+    // Every patient with readings, and their drops in each phase. Unlike the
+    // paired comparison, patients measured on one side only are kept: their
+    // summary row should be blank on the other.
+    let mut recomputed: BTreeMap<&str, [DropStats; 2]> = BTreeMap::new();
+    for r in readings {
+        recomputed.entry(r.mrn.as_str()).or_default();
+    }
+    let drops = orthostatic_drops(readings);
+    for drop in &drops {
+        let phases = recomputed.entry(drop.mrn.as_str()).or_default();
+        phases[usize::from(drop.phase == Phase::Post)].add(drop);
+    }
+    let mean = |stats: &DropStats| stats.systolic(Aggregation::Pooled);
+
+    let mut report = ConsistencyReport {
+        tolerance,
+        checked: 0,
+        discrepancies: Vec::new(),
+        only_in_summary: Vec::new(),
+        only_in_readings: Vec::new(),
+    };
+    let mut seen = BTreeSet::new();
+    for row in summary {
+        seen.insert(row.mrn.as_str());
+        let Some([pre, post]) = recomputed.get(row.mrn.as_str()) else {
+            report.only_in_summary.push(row.mrn.clone());
+            continue;
+        };
+        report.checked += 1;
+        let (pre, post) = (mean(pre), mean(post));
+        let change = pre.zip(post).map(|(pre, post)| pre - post);
+        let mut measures = vec![
+            (Measure::Pre, row.pre, pre),
+            (Measure::Post, row.post, post),
+        ];
+        // The change column is optional; without it there is nothing new to check.
+        if row.change.is_some() {
+            measures.push((Measure::Change, row.change, change));
+        }
+        for (measure, summary, recomputed) in measures {
+            if differs(summary, recomputed, tolerance) {
+                report.discrepancies.push(Discrepancy {
+                    mrn: row.mrn.clone(),
+                    number: row.number,
+                    measure,
+                    summary,
+                    recomputed,
+                });
+            }
+        }
+    }
+    report.only_in_readings = recomputed
+        .keys()
+        .filter(|mrn| !seen.contains(*mrn))
+        .map(|mrn| mrn.to_string())
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::records::Position;
+    use chrono::NaiveDateTime;
+
+    fn reading(mrn: &str, time: &str, pos: Position, sys: i32, phase: Phase) -> BpRecord {
+        BpRecord {
+            mrn: mrn.to_string(),
+            time: NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap(),
+            sys: Some(sys),
+            dia: Some(70),
+            hr: Some(80),
+            pos,
+            phase,
+        }
+    }
+
+    /// A stand test from lying at 140 to standing at `140 - drop`, starting
+    /// on the hour.
+    fn stand_test(mrn: &str, hour: &str, drop: i32, phase: Phase) -> [BpRecord; 2] {
+        [
+            reading(mrn, &format!("{hour}:00"), Position::Lying, 140, phase),
+            reading(
+                mrn,
+                &format!("{hour}:03"),
+                Position::Standing,
+                140 - drop,
+                phase,
+            ),
+        ]
+    }
+
+    fn row(mrn: &str, pre: Option<f64>, post: Option<f64>) -> SummaryRecord {
+        SummaryRecord {
+            mrn: mrn.to_string(),
+            number: Some(1),
+            pre,
+            post,
+            change: None,
+        }
+    }
+
+    /// Before the procedure SU001 has stand tests of 20 and 30 mmHg on one
+    /// visit and of 40 on another: a pooled mean of 30, where the mean of
+    /// the visit means would be 32.5. After it, one of 10. SU002 has one of
+    /// 25 before and none after.
+    fn readings() -> Vec<BpRecord> {
+        [
+            stand_test("SU001", "2025-03-01 09", 20, Phase::Pre),
+            stand_test("SU001", "2025-03-01 10", 30, Phase::Pre),
+            stand_test("SU001", "2025-03-08 09", 40, Phase::Pre),
+            stand_test("SU001", "2025-06-01 09", 10, Phase::Post),
+            stand_test("SU002", "2025-03-02 09", 25, Phase::Pre),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn matching_rows_pass() {
+        let summary = [
+            row("SU001", Some(30.0), Some(10.0)),
+            row("SU002", Some(25.0), None),
+        ];
+        let report = check(&readings(), &summary, DEFAULT_TOLERANCE);
+        assert_eq!(report.checked, 2);
+        assert!(report.is_consistent(), "{report:?}");
+    }
+
+    #[test]
+    fn differences_beyond_the_tolerance_are_listed() {
+        let mut summary = [
+            row("SU001", Some(30.04), Some(10.2)),
+            row("SU002", Some(25.0), Some(5.0)),
+        ];
+        summary[0].change = Some(20.0);
+        let report = check(&readings(), &summary, DEFAULT_TOLERANCE);
+        let found: Vec<_> = report
+            .discrepancies
+            .iter()
+            .map(|d| (d.mrn.as_str(), d.measure, d.summary, d.recomputed))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("SU001", Measure::Post, Some(10.2), Some(10.0)),
+                ("SU002", Measure::Post, Some(5.0), None),
+            ]
+        );
+        assert!((report.discrepancies[0].difference().unwrap() + 0.2).abs() < 1e-9);
+        assert_eq!(report.patients_differing(), 2);
+        assert_eq!(check(&readings(), &summary, 0.5).discrepancies.len(), 1);
+    }
+
+    #[test]
+    fn patients_in_one_file_only_are_listed() {
+        let summary = [
+            row("SU001", Some(30.0), Some(10.0)),
+            row("SU003", Some(12.0), Some(8.0)),
+        ];
+        let report = check(&readings(), &summary, DEFAULT_TOLERANCE);
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.only_in_summary, ["SU003"]);
+        assert_eq!(report.only_in_readings, ["SU002"]);
+        assert!(report.discrepancies.is_empty());
+        assert!(!report.is_consistent());
+    }
+}
@@ -0,0 +1,335 @@
+//! The study exports bundled with the deck, loaded together so every page
+//! that shows results works from the same records.
+//!
+//! The files are the de-identified copies that `build.rs` writes to
+//! `OUT_DIR`: patients are known by study ID and their dates are shifted.
+//! A CSV or workbook opened at runtime replaces the datasets it contains;
+//! it goes through the same parsers and validation as the bundled files.
+//!
+//! Prescriptions and a midodrine equivalence table are optional: nothing is
+//! bundled, and once prescriptions are opened the MEQ export is checked
+//! against them. Procedure dates are optional too; the bundled export may be
+//! empty, and patients without a date get an estimated one.
+
+use crate::data::cohort::Cohort;
+use crate::data::consistency::{check, ConsistencyReport, DEFAULT_TOLERANCE};
+use crate::data::ingest::{
+    load_csv, load_sheet, CsvRecord, DatasetSummary, IngestError, LoadReport,
+};
+use crate::data::medication::{
+    check_meq, compute_meq, EquivalenceTable, MeqCheck, DEFAULT_MEQ_TOLERANCE,
+};
+use crate::data::missing::{bp_missingness, outcome_missingness, Missingness};
+use crate::data::mrn::first_mrn;
+use crate::data::records::{
+    BpRecord, CgiRecord, EquivalenceRecord, MeqRecord, PrescriptionRecord, ProcedureRecord,
+    SummaryRecord,
+};
+use crate::data::timepoints::ProcedureDates;
+use crate::data::workbook::{
+    is_workbook, Workbook, SHEET_BP, SHEET_CGI, SHEET_EQUIVALENTS, SHEET_MEQ, SHEET_PRESCRIPTIONS,
+    SHEET_PROCEDURES, SHEET_SUMMARY,
+};
+use std::fmt;
+
+const CSV_BP: &str = include_str!(concat!(env!("OUT_DIR"), "/standup_results.csv"));
+const CSV_MEQ: &str = include_str!(concat!(env!("OUT_DIR"), "/standup_results_meq.csv"));
+const CSV_CGI: &str = include_str!(concat!(env!("OUT_DIR"), "/standup_results_cgi.csv"));
+const CSV_SUMMARY: &str = include_str!(concat!(env!("OUT_DIR"), "/standup_results_bp.csv"));
+const CSV_PROCEDURES: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/standup_results_procedures.csv"));
+
+fn bundled_csv<T: CsvRecord>(content: &str) -> LoadReport<T> {
+    if content.is_empty() {
+        return LoadReport::empty();
+    }
+    load_csv(content).unwrap_or_else(LoadReport::failed)
+}
+
+/// Why an opened file replaced none of the datasets.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileError {
+    /// Not UTF-8 text, not a workbook that opens, or not CSV at all.
+    Unreadable(String),
+    /// Neither its headers nor its sheets match any dataset.
+    Unrecognised,
+    /// Recognised as a dataset, but every row was rejected.
+    NoRecords(&'static str),
+    /// Has an MRN-shaped string, so it was not de-identified. Only where is
+    /// kept, so the message does not show the MRN.
+    Identified { location: String, line: usize },
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Unreadable(reason) => write!(f, "{reason}"),
+            FileError::Unrecognised => {
+                write!(
+                    f,
+                    "no BP, MEQ, CGI-I, BP summary, prescription, MEQ equivalence or procedure date data found (check the column headers)"
+                )
+            }
+            FileError::NoRecords(dataset) => write!(f, "{dataset}: no row passed validation"),
+            FileError::Identified { location, line } => write!(
+                f,
+                "{location}, line {line}: looks like an MRN; only de-identified data, with study IDs, can be opened"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datasets {
+    pub bp: LoadReport<BpRecord>,
+    pub meq: LoadReport<MeqRecord>,
+    pub cgi: LoadReport<CgiRecord>,
+    /// The spreadsheet's per-patient summary, only used to cross-check `bp`.
+    pub summary: LoadReport<SummaryRecord>,
+    /// Optional; when present, the source of truth for `meq`.
+    pub prescriptions: LoadReport<PrescriptionRecord>,
+    /// Optional overrides of the default [`EquivalenceTable`].
+    pub equivalents: LoadReport<EquivalenceRecord>,
+    /// Optional; see [`crate::data::timepoints`].
+    pub procedures: LoadReport<ProcedureRecord>,
+}
+
+impl Datasets {
+    /// The datasets [`Datasets::bundled`] fills from the compiled-in exports.
+    pub const BUNDLED: [&'static str; 5] = [
+        BpRecord::DATASET,
+        MeqRecord::DATASET,
+        CgiRecord::DATASET,
+        SummaryRecord::DATASET,
+        ProcedureRecord::DATASET,
+    ];
+
+    /// Parses the exports compiled into the binary. A file that cannot be
+    /// read leaves its dataset empty, with the error kept for diagnostics; an
+    /// empty file, from a build without `private/`, is just an empty dataset.
+    pub fn bundled() -> Self {
+        Self {
+            bp: bundled_csv(CSV_BP),
+            meq: bundled_csv(CSV_MEQ),
+            cgi: bundled_csv(CSV_CGI),
+            summary: bundled_csv(CSV_SUMMARY),
+            prescriptions: LoadReport::empty(),
+            equivalents: LoadReport::empty(),
+            procedures: bundled_csv(CSV_PROCEDURES),
+        }
+    }
+
+    /// Reads each dataset from its sheet of a study workbook. As with the
+    /// bundled files, a sheet that cannot be read leaves its dataset empty;
+    /// the optional sheets may simply be absent.
+    pub fn from_workbook(workbook: &mut Workbook) -> Self {
+        Self {
+            bp: load_sheet(workbook, SHEET_BP).unwrap_or_else(LoadReport::failed),
+            meq: load_sheet(workbook, SHEET_MEQ).unwrap_or_else(LoadReport::failed),
+            cgi: load_sheet(workbook, SHEET_CGI).unwrap_or_else(LoadReport::failed),
+            summary: load_sheet(workbook, SHEET_SUMMARY).unwrap_or_else(LoadReport::failed),
+            prescriptions: load_sheet(workbook, SHEET_PRESCRIPTIONS)
+                .unwrap_or_else(|_| LoadReport::empty()),
+            equivalents: load_sheet(workbook, SHEET_EQUIVALENTS)
+                .unwrap_or_else(|_| LoadReport::empty()),
+            procedures: load_sheet(workbook, SHEET_PROCEDURES)
+                .unwrap_or_else(|_| LoadReport::empty()),
+        }
+    }
+
+    /// The optional datasets are listed once something was loaded for them.
+    pub fn summaries(&self) -> Vec<DatasetSummary> {
+        let mut summaries = vec![
+            self.bp.summary(),
+            self.meq.summary(),
+            self.cgi.summary(),
+            self.summary.summary(),
+        ];
+        for optional in [
+            self.prescriptions.summary(),
+            self.equivalents.summary(),
+            self.procedures.summary(),
+        ] {
+            if optional.accepted > 0 || !optional.rejected.is_empty() || optional.error.is_some() {
+                summaries.push(optional);
+            }
+        }
+        summaries
+    }
+
+    /// The summary sheet checked against the readings; see
+    /// [`crate::data::consistency`].
+    pub fn consistency(&self) -> ConsistencyReport {
+        check(&self.bp.records, &self.summary.records, DEFAULT_TOLERANCE)
+    }
+
+    pub fn equivalence_table(&self) -> EquivalenceTable {
+        EquivalenceTable::with(&self.equivalents.records)
+    }
+
+    /// The MEQ export checked against the prescriptions, if any were loaded;
+    /// see [`crate::data::medication`].
+    pub fn meq_check(&self) -> Option<MeqCheck> {
+        if self.prescriptions.records.is_empty() {
+            return None;
+        }
+        let computed = compute_meq(&self.prescriptions.records, &self.equivalence_table());
+        Some(check_meq(
+            &self.meq.records,
+            &computed,
+            DEFAULT_MEQ_TOLERANCE,
+        ))
+    }
+
+    /// What each analysis is missing, hemodynamic analyses first; see
+    /// [`crate::data::missing`].
+    pub fn missingness(&self) -> Vec<Missingness> {
+        let mut analyses = bp_missingness(&self.bp);
+        analyses.extend(outcome_missingness(
+            &self.bp.records,
+            &self.meq.records,
+            &self.cgi.records,
+        ));
+        analyses
+    }
+
+    /// Loaded procedure dates, estimated where missing.
+    pub fn procedure_dates(&self) -> ProcedureDates {
+        ProcedureDates::build(&self.bp.records, &self.procedures.records)
+    }
+
+    pub fn cohort(&self) -> Cohort {
+        Cohort::build(&self.bp.records, &self.meq.records, &self.cgi.records)
+    }
+
+    /// Replaces the datasets found in an opened file and returns their
+    /// names. A workbook replaces every dataset whose sheet loads with at
+    /// least one record; a CSV replaces the one dataset its headers fit.
+    /// A file with an MRN-shaped string anywhere in it is refused, since
+    /// the app has no study ID mapping to de-identify it with. On error
+    /// nothing is replaced.
+    pub fn load_file(
+        &mut self,
+        name: &str,
+        bytes: Vec<u8>,
+    ) -> Result<Vec<&'static str>, FileError> {
+        if is_workbook(name) {
+            let mut workbook =
+                Workbook::open(bytes).map_err(|e| FileError::Unreadable(e.to_string()))?;
+            for sheet in workbook.sheet_names() {
+                let text = workbook
+                    .sheet_csv(&sheet, &[])
+                    .map_err(|e| FileError::Unreadable(e.to_string()))?;
+                refuse_mrns(&format!("{name}, sheet '{sheet}'"), &text)?;
+            }
+            let loaded = Self::from_workbook(&mut workbook);
+            let replaced: Vec<&'static str> = [
+                replace_usable(&mut self.bp, loaded.bp),
+                replace_usable(&mut self.meq, loaded.meq),
+                replace_usable(&mut self.cgi, loaded.cgi),
+                replace_usable(&mut self.summary, loaded.summary),
+                replace_usable(&mut self.prescriptions, loaded.prescriptions),
+                replace_usable(&mut self.equivalents, loaded.equivalents),
+                replace_usable(&mut self.procedures, loaded.procedures),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            return if replaced.is_empty() {
+                Err(FileError::Unrecognised)
+            } else {
+                Ok(replaced)
+            };
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|_| FileError::Unreadable("not a CSV or workbook file".to_string()))?;
+        refuse_mrns(name, &text)?;
+        replace_csv(&mut self.bp, &text)
+            .or_else(|| replace_csv(&mut self.meq, &text))
+            .or_else(|| replace_csv(&mut self.cgi, &text))
+            .or_else(|| replace_csv(&mut self.summary, &text))
+            .or_else(|| replace_csv(&mut self.prescriptions, &text))
+            .or_else(|| replace_csv(&mut self.equivalents, &text))
+            .or_else(|| replace_csv(&mut self.procedures, &text))
+            .unwrap_or(Err(FileError::Unrecognised))
+            .map(|dataset| vec![dataset])
+    }
+}
+
+/// Fails on the first MRN-shaped string in `text`, giving its line.
+fn refuse_mrns(location: &str, text: &str) -> Result<(), FileError> {
+    match first_mrn(text.as_bytes()) {
+        Some(offset) => Err(FileError::Identified {
+            location: location.to_string(),
+            line: text[..offset].matches('\n').count() + 1,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Keeps a sheet's dataset only if it loaded and has records, so a workbook
+/// without, say, a CGI-I sheet leaves the current scores in place.
+fn replace_usable<T: CsvRecord>(
+    slot: &mut LoadReport<T>,
+    loaded: LoadReport<T>,
+) -> Option<&'static str> {
+    if loaded.error.is_some() || loaded.records.is_empty() {
+        return None;
+    }
+    *slot = loaded;
+    Some(T::DATASET)
+}
+
+/// Loads CSV text into `slot` if its headers fit `T`; `None` if they do not.
+fn replace_csv<T: CsvRecord>(
+    slot: &mut LoadReport<T>,
+    text: &str,
+) -> Option<Result<&'static str, FileError>> {
+    match load_csv::<T>(text) {
+        Err(IngestError::MissingColumns { .. }) => None,
+        Err(e) => Some(Err(FileError::Unreadable(e.to_string()))),
+        Ok(report) if report.records.is_empty() => Some(Err(FileError::NoRecords(T::DATASET))),
+        Ok(report) => {
+            *slot = report;
+            Some(Ok(T::DATASET))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "PAT_MRN_ID,CGI-I Score";
+
+    #[test]
+    fn a_file_with_an_mrn_is_refused() {
+        let mut data = Datasets::bundled();
+        let before = data.clone();
+        // Built up so this file does not fail the build's own check.
+        let csv = format!("{HEADER}\nSU001,2\nE{},3\n", "1234567");
+        let error = data.load_file("cgi.csv", csv.into_bytes()).unwrap_err();
+        assert_eq!(
+            error,
+            FileError::Identified {
+                location: "cgi.csv".to_string(),
+                line: 3
+            }
+        );
+        assert!(!error.to_string().contains("1234567"));
+        assert_eq!(data, before);
+    }
+
+    #[test]
+    fn a_de_identified_file_loads() {
+        let mut data = Datasets::bundled();
+        let csv = format!("{HEADER}\nSU001,2\nSU002,3\n");
+        assert_eq!(
+            data.load_file("cgi.csv", csv.into_bytes()),
+            Ok(vec![CgiRecord::DATASET])
+        );
+        assert_eq!(data.cgi.records.len(), 2);
+    }
+}
@@ -0,0 +1,315 @@
+//! Orthostatic drop per patient, paired across the procedure.
+//!
+//! Pooling every lying and every standing reading mixes patients who were
+//! measured once with patients measured dozens of times. Instead, each
+//! lying reading is paired with the standing reading that follows it in the
+//! same stand test, each patient's drops are reduced to one value per phase by
+//! the chosen [`Aggregation`] over their visits, and only patients measured
+//! both before and after the procedure are compared.
+//!
+//! The heart rate response comes from the same stand tests: the increment
+//! on standing, and its ratio to the systolic drop, which separates
+//! neurogenic from non-neurogenic OH.
+
+use crate::data::orthostatic::SYSTOLIC_THRESHOLD;
+use crate::data::records::{BpRecord, Phase, Position};
+use crate::data::visits::{visits, Aggregation, Worst};
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+
+/// ΔHR/ΔSBP in bpm per mmHg below which OH is taken as neurogenic: the
+/// baroreflex fails to raise the heart rate in proportion to the fall in
+/// pressure (Norcliffe-Kaufmann et al., 2018).
+pub const NEUROGENIC_RATIO: f64 = 0.5;
+
+/// Lying minus standing pressure for one stand test, and the rise in heart
+/// rate that went with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrthostaticDrop {
+    pub mrn: String,
+    pub phase: Phase,
+    /// When the lying reading was taken.
+    pub time: NaiveDateTime,
+    /// Which of the patient's visits in this phase, counting from 0.
+    pub visit: usize,
+    pub sys: i32,
+    pub dia: Option<i32>,
+    /// Standing minus lying heart rate: positive when it rose.
+    pub hr: Option<i32>,
+}
+
+/// Pairs each lying reading with the first standing reading after it in
+/// the same stand test, per patient and phase, in `RECORDED_TIME` order.
+/// Readings without a systolic value cannot anchor or complete a sequence.
+pub fn orthostatic_drops(records: &[BpRecord]) -> Vec<OrthostaticDrop> {
+    let mut drops = Vec::new();
+    for visit in visits(records) {
+        for test in visit.stand_tests() {
+            let mut lying: Option<&BpRecord> = None;
+            for r in test.iter().filter(|r| r.sys.is_some()) {
+                match r.pos {
+                    Position::Lying => lying = Some(r),
+                    Position::Sitting => {}
+                    Position::Standing => {
+                        let Some(base) = lying.take() else { continue };
+                        drops.push(OrthostaticDrop {
+                            mrn: r.mrn.clone(),
+                            phase: r.phase,
+                            time: base.time,
+                            visit: visit.index,
+                            sys: base.sys.unwrap_or_default() - r.sys.unwrap_or_default(),
+                            dia: base.dia.zip(r.dia).map(|(l, s)| l - s),
+                            hr: base.hr.zip(r.hr).map(|(l, s)| s - l),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    drops
+}
+
+/// One patient's drops in one phase, by visit.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DropStats {
+    pub mrn: String,
+    /// The systolic drop of each stand test, one list per visit in time
+    /// order. A visit without a complete stand test has an empty list.
+    pub sys: Vec<Vec<f64>>,
+    /// Diastolic drops likewise, for the stand tests that have one.
+    pub dia: Vec<Vec<f64>>,
+    /// Heart rate increments likewise.
+    pub hr: Vec<Vec<f64>>,
+    /// ΔHR/ΔSBP likewise, for the stand tests with a heart rate increment
+    /// and a systolic drop at the OH threshold.
+    pub ratio: Vec<Vec<f64>>,
+    /// First and last stand test in the phase.
+    pub span: Option<(NaiveDateTime, NaiveDateTime)>,
+}
+
+impl DropStats {
+    pub(crate) fn add(&mut self, drop: &OrthostaticDrop) {
+        if self.sys.len() <= drop.visit {
+            self.sys.resize(drop.visit + 1, Vec::new());
+            self.dia.resize(drop.visit + 1, Vec::new());
+            self.hr.resize(drop.visit + 1, Vec::new());
+            self.ratio.resize(drop.visit + 1, Vec::new());
+        }
+        self.sys[drop.visit].push(f64::from(drop.sys));
+        self.dia[drop.visit].extend(drop.dia.map(f64::from));
+        self.hr[drop.visit].extend(drop.hr.map(f64::from));
+        if drop.sys >= SYSTOLIC_THRESHOLD {
+            self.ratio[drop.visit].extend(drop.hr.map(|hr| f64::from(hr) / f64::from(drop.sys)));
+        }
+        self.span = Some(match self.span {
+            None => (drop.time, drop.time),
+            Some((first, last)) => (first.min(drop.time), last.max(drop.time)),
+        });
+    }
+
+    /// Number of stand tests in the phase.
+    pub fn tests(&self) -> usize {
+        self.sys.iter().map(Vec::len).sum()
+    }
+
+    /// Number of visits with at least one stand test.
+    pub fn visits(&self) -> usize {
+        self.sys.iter().filter(|v| !v.is_empty()).count()
+    }
+
+    /// The systolic drop for the phase under `rule`; the worst is the largest.
+    pub fn systolic(&self, rule: Aggregation) -> Option<f64> {
+        rule.apply(&self.sys, Worst::Highest)
+    }
+
+    pub fn diastolic(&self, rule: Aggregation) -> Option<f64> {
+        rule.apply(&self.dia, Worst::Highest)
+    }
+
+    /// The heart rate increment on standing under `rule`; the worst is the
+    /// largest, as in postural tachycardia.
+    pub fn hr_increment(&self, rule: Aggregation) -> Option<f64> {
+        rule.apply(&self.hr, Worst::Highest)
+    }
+
+    /// ΔHR/ΔSBP under `rule`. Only defined when the systolic drop reaches
+    /// the OH threshold; below it the ratio does not mean anything. The
+    /// ratio is taken per stand test and then aggregated, so a heart rate
+    /// and a drop from different tests are never divided; the worst is the
+    /// lowest, the most neurogenic.
+    pub fn hr_ratio(&self, rule: Aggregation) -> Option<f64> {
+        self.systolic(rule)
+            .filter(|d| *d >= f64::from(SYSTOLIC_THRESHOLD))?;
+        rule.apply(&self.ratio, Worst::Lowest)
+    }
+
+    pub fn mechanism(&self, rule: Aggregation) -> Option<OhMechanism> {
+        self.hr_ratio(rule).map(|ratio| {
+            if ratio < NEUROGENIC_RATIO {
+                OhMechanism::Neurogenic
+            } else {
+                OhMechanism::NonNeurogenic
+            }
+        })
+    }
+}
+
+/// What ΔHR/ΔSBP suggests is behind a patient's OH.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OhMechanism {
+    Neurogenic,
+    NonNeurogenic,
+}
+
+impl OhMechanism {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OhMechanism::Neurogenic => "Neurogenic",
+            OhMechanism::NonNeurogenic => "Non-neurogenic",
+        }
+    }
+}
+
+/// Patients with a systolic drop at the OH threshold in one phase, split by
+/// [`OhMechanism`]. Those without heart rates are counted apart.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MechanismCounts {
+    pub neurogenic: usize,
+    pub non_neurogenic: usize,
+    /// A large enough drop, but no heart rate increment to judge it by.
+    pub unknown: usize,
+}
+
+impl MechanismCounts {
+    pub fn count<'a>(phases: impl IntoIterator<Item = &'a DropStats>, rule: Aggregation) -> Self {
+        let mut counts = Self::default();
+        for stats in phases {
+            if stats
+                .systolic(rule)
+                .is_none_or(|d| d < f64::from(SYSTOLIC_THRESHOLD))
+            {
+                continue;
+            }
+            match stats.mechanism(rule) {
+                Some(OhMechanism::Neurogenic) => counts.neurogenic += 1,
+                Some(OhMechanism::NonNeurogenic) => counts.non_neurogenic += 1,
+                None => counts.unknown += 1,
+            }
+        }
+        counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.neurogenic + self.non_neurogenic + self.unknown
+    }
+}
+
+/// A patient with stand tests on both sides of the procedure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatientDrop {
+    pub pre: DropStats,
+    pub post: DropStats,
+}
+
+/// Patients with at least one stand test before and one after, in MRN order.
+pub fn paired_drops(drops: &[OrthostaticDrop]) -> Vec<PatientDrop> {
+    let mut by_patient: BTreeMap<&str, (DropStats, DropStats)> = BTreeMap::new();
+    for drop in drops {
+        let (pre, post) = by_patient.entry(drop.mrn.as_str()).or_insert_with(|| {
+            let empty = DropStats {
+                mrn: drop.mrn.clone(),
+                ..DropStats::default()
+            };
+            (empty.clone(), empty)
+        });
+        match drop.phase {
+            Phase::Pre => pre.add(drop),
+            Phase::Post => post.add(drop),
+        }
+    }
+    by_patient
+        .into_values()
+        .filter(|(pre, post)| pre.tests() > 0 && post.tests() > 0)
+        .map(|(pre, post)| PatientDrop { pre, post })
+        .collect()
+}
+
+/// (pre, post) systolic drop for each patient under `rule`.
+pub fn systolic_pairs(patients: &[PatientDrop], rule: Aggregation) -> Vec<(f64, f64)> {
+    patients
+        .iter()
+        .filter_map(|p| p.pre.systolic(rule).zip(p.post.systolic(rule)))
+        .collect()
+}
+
+/// (pre, post) diastolic drop under `rule`, for each patient that has one.
+pub fn diastolic_pairs(patients: &[PatientDrop], rule: Aggregation) -> Vec<(f64, f64)> {
+    patients
+        .iter()
+        .filter_map(|p| p.pre.diastolic(rule).zip(p.post.diastolic(rule)))
+        .collect()
+}
+
+/// (pre, post) heart rate increment under `rule`, for each patient that has one.
+pub fn hr_increment_pairs(patients: &[PatientDrop], rule: Aggregation) -> Vec<(f64, f64)> {
+    patients
+        .iter()
+        .filter_map(|p| p.pre.hr_increment(rule).zip(p.post.hr_increment(rule)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::orthostatic::sessions;
+
+    fn reading(time: &str, pos: Position, sys: i32, hr: i32) -> BpRecord {
+        BpRecord {
+            mrn: "SU001".to_string(),
+            time: NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap(),
+            sys: Some(sys),
+            dia: Some(70),
+            hr: Some(hr),
+            pos,
+            phase: Phase::Pre,
+        }
+    }
+
+    /// Two stand tests on one day and one the next.
+    fn records() -> Vec<BpRecord> {
+        vec![
+            reading("2025-03-01 09:00", Position::Lying, 140, 60),
+            reading("2025-03-01 09:05", Position::Standing, 100, 70),
+            reading("2025-03-01 11:00", Position::Lying, 130, 60),
+            reading("2025-03-01 11:05", Position::Standing, 110, 90),
+            reading("2025-03-02 09:00", Position::Lying, 120, 60),
+            reading("2025-03-02 09:05", Position::Standing, 115, 62),
+        ]
+    }
+
+    #[test]
+    fn drops_and_sessions_share_stand_tests_and_visits() {
+        let records = records();
+        let drops = orthostatic_drops(&records);
+        let sessions = sessions(&records);
+        assert_eq!(drops.len(), 3);
+        assert_eq!(sessions.len(), 3);
+        let visits: Vec<usize> = drops.iter().map(|d| d.visit).collect();
+        assert_eq!(visits, [0, 0, 1]);
+        assert_eq!(visits, sessions.iter().map(|s| s.visit).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn hr_ratio_is_taken_per_stand_test() {
+        let mut stats = DropStats::default();
+        for drop in orthostatic_drops(&records()) {
+            stats.add(&drop);
+        }
+        // Drops of 40 and 20 mmHg with increments of 10 and 30 bpm: the
+        // largest drop and the largest increment would give 0.75.
+        assert_eq!(stats.hr_ratio(Aggregation::Worst), Some(0.25));
+        assert_eq!(stats.hr_ratio(Aggregation::First), Some(0.875));
+        // The second day's 5 mmHg drop is below the threshold.
+        assert_eq!(stats.hr_ratio(Aggregation::Last), None);
+    }
+}
@@ -0,0 +1,400 @@
+//! Header-mapped CSV loading for the study exports.
+//!
+//! Columns are matched by header name, so a reordered export still loads.
+//! Rows that fail to parse or validate are not dropped silently: each one is
+//! recorded with its line number and the reason, for the diagnostics panel.
+//! A value that parses but cannot be real is cleared instead, keeping the rest
+//! of its row, and recorded the same way.
+
+use crate::data::workbook::Workbook;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A row type that can be read from one of the study exports.
+pub trait CsvRecord: DeserializeOwned {
+    /// Human-readable name of the dataset, shown in diagnostics.
+    const DATASET: &'static str;
+    /// Headers that must be present for the file to be usable at all.
+    const REQUIRED_COLUMNS: &'static [&'static str];
+    /// For exports with a footer below the table: loading stops at the first
+    /// row where this column is blank.
+    const TABLE_END_COLUMN: Option<&'static str> = None;
+
+    /// Clears optional values that cannot be real, such as a heart rate of
+    /// 0, and says why for each. Runs before [`CsvRecord::validate`].
+    fn clean(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Checks beyond what the types enforce, such as a score being on its scale.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The column every patient-level export identifies patients by.
+const ID_COLUMN: &str = "PAT_MRN_ID";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedRow {
+    /// 1-based line number in the source file.
+    pub line: u64,
+    /// The row's `PAT_MRN_ID`, when it has one.
+    pub mrn: Option<String>,
+    pub reason: String,
+}
+
+/// A value blanked by [`CsvRecord::clean`]; its row was kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearedValue {
+    pub line: u64,
+    pub mrn: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngestError {
+    /// The header row lacks columns the dataset needs.
+    MissingColumns {
+        dataset: &'static str,
+        missing: Vec<&'static str>,
+    },
+    /// The file could not be read as CSV, e.g. no header row.
+    Unreadable {
+        dataset: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::MissingColumns { dataset, missing } => {
+                write!(f, "{dataset}: missing column(s) {}", missing.join(", "))
+            }
+            IngestError::Unreadable { dataset, reason } => write!(f, "{dataset}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadReport<T> {
+    pub dataset: &'static str,
+    pub records: Vec<T>,
+    pub rejected: Vec<RejectedRow>,
+    pub cleared: Vec<ClearedValue>,
+    /// Set when the whole file was unusable; `records` is then empty.
+    pub error: Option<IngestError>,
+}
+
+impl<T: CsvRecord> LoadReport<T> {
+    /// An empty report standing in for a file that could not be loaded.
+    pub fn failed(error: IngestError) -> Self {
+        Self {
+            dataset: T::DATASET,
+            records: Vec::new(),
+            rejected: Vec::new(),
+            cleared: Vec::new(),
+            error: Some(error),
+        }
+    }
+
+    /// An optional dataset that was not supplied.
+    pub fn empty() -> Self {
+        Self {
+            dataset: T::DATASET,
+            records: Vec::new(),
+            rejected: Vec::new(),
+            cleared: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn summary(&self) -> DatasetSummary {
+        DatasetSummary {
+            dataset: self.dataset,
+            accepted: self.records.len(),
+            rejected: self.rejected.clone(),
+            cleared: self.cleared.clone(),
+            error: self.error.as_ref().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// What the diagnostics panel shows for one dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetSummary {
+    pub dataset: &'static str,
+    pub accepted: usize,
+    pub rejected: Vec<RejectedRow>,
+    pub cleared: Vec<ClearedValue>,
+    pub error: Option<String>,
+}
+
+/// Markers the exports use for an empty cell besides leaving it blank.
+const MISSING_MARKERS: &[&str] = &["NULL", "NA", "N/A"];
+
+/// `deserialize_with` helper for optional numeric cells: blank cells and
+/// missing markers such as `NULL` become `None`, anything else must parse.
+pub fn optional_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    match raw.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(s) if MISSING_MARKERS.iter().any(|m| s.eq_ignore_ascii_case(m)) => Ok(None),
+        Some(s) => s
+            .parse()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("'{s}' is not a number"))),
+    }
+}
+
+/// Turns a csv deserialisation error into "<column>: <problem>".
+///
+/// Errors raised by a `deserialize_with` helper such as [`optional_number`]
+/// carry no column, so the cell they quote is looked up in the row instead.
+fn describe(err: &csv::Error, headers: &csv::StringRecord, row: &csv::StringRecord) -> String {
+    match err.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => {
+            let quoted = match err.kind() {
+                csv::DeserializeErrorKind::Message(msg) => msg.split('\'').nth(1),
+                _ => None,
+            };
+            let column = err
+                .field()
+                .map(|i| i as usize)
+                .or_else(|| row.iter().position(|cell| Some(cell) == quoted))
+                .and_then(|i| headers.get(i))
+                .unwrap_or("row");
+            format!("{column}: {}", err.kind())
+        }
+        _ => err.to_string(),
+    }
+}
+
+pub fn load_csv<T: CsvRecord>(content: &str) -> Result<LoadReport<T>, IngestError> {
+    // Spreadsheet exports often start with a UTF-8 byte order mark and use
+    // CRLF endings; the csv reader miscounts line numbers on the latter.
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let content = content.replace("\r\n", "\n");
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| IngestError::Unreadable {
+            dataset: T::DATASET,
+            reason: e.to_string(),
+        })?
+        .clone();
+    let missing: Vec<&'static str> = T::REQUIRED_COLUMNS
+        .iter()
+        .copied()
+        .filter(|col| !headers.iter().any(|h| h == *col))
+        .collect();
+    if !missing.is_empty() {
+        return Err(IngestError::MissingColumns {
+            dataset: T::DATASET,
+            missing,
+        });
+    }
+
+    let table_end = T::TABLE_END_COLUMN.and_then(|col| headers.iter().position(|h| h == col));
+    let id = headers.iter().position(|h| h == ID_COLUMN);
+    let mut records = Vec::new();
+    let mut rejected = Vec::new();
+    let mut cleared = Vec::new();
+    for result in reader.records() {
+        let row = match result {
+            Ok(row) => row,
+            Err(e) => {
+                rejected.push(RejectedRow {
+                    line: e.position().map_or(0, |p| p.line()),
+                    mrn: None,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+        // Blank spacer rows left over from the spreadsheet are not data.
+        if row.iter().all(str::is_empty) {
+            continue;
+        }
+        if table_end.is_some_and(|i| row.get(i).is_none_or(str::is_empty)) {
+            break;
+        }
+        let line = row.position().map_or(0, |p| p.line());
+        let mrn = id
+            .and_then(|i| row.get(i))
+            .filter(|m| !m.is_empty())
+            .map(String::from);
+        match row.deserialize::<T>(Some(&headers)) {
+            Ok(mut record) => {
+                let notes = record.clean();
+                match record.validate() {
+                    Ok(()) => {
+                        records.push(record);
+                        cleared.extend(notes.into_iter().map(|reason| ClearedValue {
+                            line,
+                            mrn: mrn.clone(),
+                            reason,
+                        }));
+                    }
+                    Err(reason) => rejected.push(RejectedRow { line, mrn, reason }),
+                }
+            }
+            Err(e) => rejected.push(RejectedRow {
+                line,
+                mrn,
+                reason: describe(&e, &headers, &row),
+            }),
+        }
+    }
+
+    Ok(LoadReport {
+        dataset: T::DATASET,
+        records,
+        rejected,
+        cleared,
+        error: None,
+    })
+}
+
+/// Loads a dataset from its sheet of the study workbook; see
+/// [`Workbook::sheet_csv`] for how the sheet is found.
+pub fn load_sheet<T: CsvRecord>(
+    workbook: &mut Workbook,
+    sheet: &str,
+) -> Result<LoadReport<T>, IngestError> {
+    let content = workbook
+        .sheet_csv(sheet, T::REQUIRED_COLUMNS)
+        .map_err(|e| IngestError::Unreadable {
+            dataset: T::DATASET,
+            reason: e.to_string(),
+        })?;
+    load_csv(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        #[serde(rename = "PAT_MRN_ID")]
+        mrn: String,
+        #[serde(rename = "Score")]
+        score: i32,
+        #[serde(rename = "Note", default)]
+        note: String,
+        #[serde(rename = "Dose", default, deserialize_with = "optional_number")]
+        dose: Option<f64>,
+    }
+
+    impl CsvRecord for Row {
+        const DATASET: &'static str = "test rows";
+        const REQUIRED_COLUMNS: &'static [&'static str] = &["PAT_MRN_ID", "Score"];
+
+        fn validate(&self) -> Result<(), String> {
+            if !(1..=7).contains(&self.score) {
+                return Err(format!("Score: {} is out of range", self.score));
+            }
+            Ok(())
+        }
+    }
+
+    fn row(mrn: &str, score: i32) -> Row {
+        Row {
+            mrn: mrn.to_string(),
+            score,
+            note: String::new(),
+            dose: None,
+        }
+    }
+
+    #[test]
+    fn columns_are_matched_by_name() {
+        let report = load_csv::<Row>("Note,Score,Extra,PAT_MRN_ID\n,3,x,SU001\n").unwrap();
+        assert_eq!(report.records, [row("SU001", 3)]);
+        assert!(report.rejected.is_empty());
+    }
+
+    #[test]
+    fn missing_columns_refuse_the_file() {
+        let error = load_csv::<Row>("PAT_MRN_ID,Note\nSU001,\n").unwrap_err();
+        assert_eq!(
+            error,
+            IngestError::MissingColumns {
+                dataset: "test rows",
+                missing: vec!["Score"],
+            }
+        );
+        assert_eq!(error.to_string(), "test rows: missing column(s) Score");
+        assert!(matches!(
+            load_csv::<Row>(""),
+            Err(IngestError::MissingColumns { .. })
+        ));
+    }
+
+    #[test]
+    fn quoted_fields_keep_their_commas() {
+        let csv = "PAT_MRN_ID,Score,Note\nSU001,2,\"dizzy, then fine\"\n";
+        let report = load_csv::<Row>(csv).unwrap();
+        assert_eq!(report.records[0].note, "dizzy, then fine");
+    }
+
+    #[test]
+    fn byte_order_mark_and_crlf_are_handled() {
+        let csv = "\u{feff}PAT_MRN_ID,Score\r\nSU001,2\r\nSU002,x\r\n";
+        let report = load_csv::<Row>(csv).unwrap();
+        assert_eq!(report.records, [row("SU001", 2)]);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].line, 3);
+    }
+
+    #[test]
+    fn headers_and_cells_are_trimmed() {
+        let report = load_csv::<Row>(" PAT_MRN_ID , Score \n SU001 , 4 \n").unwrap();
+        assert_eq!(report.records, [row("SU001", 4)]);
+    }
+
+    #[test]
+    fn missing_markers_are_blank() {
+        let csv = "PAT_MRN_ID,Score,Dose\nSU001,1,NULL\nSU002,1, n/a \nSU003,1,2.5\nSU004,1,lots\n";
+        let report = load_csv::<Row>(csv).unwrap();
+        let doses: Vec<_> = report.records.iter().map(|r| r.dose).collect();
+        assert_eq!(doses, [None, None, Some(2.5)]);
+        assert_eq!(report.rejected[0].reason, "Dose: 'lots' is not a number");
+    }
+
+    /// Lines count from the header as line 1, and a quoted cell that spans
+    /// lines moves every row after it down.
+    #[test]
+    fn rejected_rows_keep_their_line_and_reason() {
+        let csv = "PAT_MRN_ID,Score,Note\n\
+                   SU001,2,\"two\nlines\"\n\
+                   ,,\n\
+                   SU002,x,\n\
+                   SU003,9,\n\
+                   ,3,\n";
+        let report = load_csv::<Row>(csv).unwrap();
+        assert_eq!(report.records.len(), 2);
+        let rejected: Vec<_> = report
+            .rejected
+            .iter()
+            .map(|r| (r.line, r.mrn.as_deref()))
+            .collect();
+        assert_eq!(rejected, [(5, Some("SU002")), (6, Some("SU003"))]);
+        assert!(report.rejected[0].reason.starts_with("Score: "));
+        assert_eq!(report.rejected[1].reason, "Score: 9 is out of range");
+    }
+}
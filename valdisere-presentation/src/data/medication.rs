@@ -0,0 +1,357 @@
+//! Per-patient response of the pressor dose, in midodrine equivalents (MEQ),
+//! and the conversion from prescriptions to MEQ. MEQ is counted in
+//! equivalence units (ME), where each drug's maximum daily dose is 100 ME.
+//!
+//! The MEQ export holds each patient's total daily pressor dose before and
+//! after the procedure, converted to ME by hand. Given
+//! the prescriptions behind it, [`compute_meq`] redoes that conversion with
+//! an [`EquivalenceTable`] and [`check_meq`] lists the patients whose stored
+//! MEQ does not follow.
+
+use crate::data::records::{Drug, EquivalenceRecord, MeqRecord, Phase, PrescriptionRecord};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Reduction, in percent, at which a patient counts as a responder.
+pub const RESPONDER_REDUCTION: f64 = 50.0;
+/// Reduction, in percent, meaning the medication was stopped.
+pub const DISCONTINUED_REDUCTION: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MeqResponse {
+    Discontinued,
+    /// At least [`RESPONDER_REDUCTION`] percent lower, but not stopped.
+    Halved,
+    Reduced,
+    Unchanged,
+    Increased,
+    /// On no medication before, some after; no percentage exists.
+    Started,
+}
+
+impl MeqResponse {
+    pub fn classify(record: &MeqRecord) -> Self {
+        let Some(reduction) = record.percent_reduction() else {
+            return if record.post > 0.0 {
+                MeqResponse::Started
+            } else {
+                MeqResponse::Unchanged
+            };
+        };
+        if reduction >= DISCONTINUED_REDUCTION {
+            MeqResponse::Discontinued
+        } else if reduction >= RESPONDER_REDUCTION {
+            MeqResponse::Halved
+        } else if reduction > 0.0 {
+            MeqResponse::Reduced
+        } else if reduction == 0.0 {
+            MeqResponse::Unchanged
+        } else {
+            MeqResponse::Increased
+        }
+    }
+
+    pub fn is_responder(&self) -> bool {
+        matches!(self, MeqResponse::Discontinued | MeqResponse::Halved)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeqResponse::Discontinued => "Discontinued",
+            MeqResponse::Halved => "≥50% reduction",
+            MeqResponse::Reduced => "<50% reduction",
+            MeqResponse::Unchanged => "Unchanged",
+            MeqResponse::Increased => "Increased",
+            MeqResponse::Started => "Started",
+        }
+    }
+}
+
+/// How many patients fall in each response category.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ResponseCounts {
+    pub discontinued: usize,
+    pub halved: usize,
+    pub reduced: usize,
+    pub unchanged: usize,
+    pub increased: usize,
+    pub started: usize,
+}
+
+impl ResponseCounts {
+    pub fn tally(records: &[MeqRecord]) -> Self {
+        let mut counts = Self::default();
+        for record in records {
+            *counts.slot(MeqResponse::classify(record)) += 1;
+        }
+        counts
+    }
+
+    fn slot(&mut self, response: MeqResponse) -> &mut usize {
+        match response {
+            MeqResponse::Discontinued => &mut self.discontinued,
+            MeqResponse::Halved => &mut self.halved,
+            MeqResponse::Reduced => &mut self.reduced,
+            MeqResponse::Unchanged => &mut self.unchanged,
+            MeqResponse::Increased => &mut self.increased,
+            MeqResponse::Started => &mut self.started,
+        }
+    }
+
+    /// Patients with at least a [`RESPONDER_REDUCTION`] percent reduction.
+    pub fn responders(&self) -> usize {
+        self.discontinued + self.halved
+    }
+
+    pub fn total(&self) -> usize {
+        self.discontinued
+            + self.halved
+            + self.reduced
+            + self.unchanged
+            + self.increased
+            + self.started
+    }
+}
+
+/// Stored and computed MEQ closer than this, in ME, agree: the export keeps
+/// one decimal place.
+pub const DEFAULT_MEQ_TOLERANCE: f64 = 0.05;
+
+/// ME that one milligram of each drug counts as.
+///
+/// The defaults make each drug's usual maximum daily dose 100 ME:
+/// midodrine 30 mg, fludrocortisone 0.3 mg, droxidopa 1800 mg and
+/// pyridostigmine 180 mg. A study using other factors loads its own table;
+/// see [`EquivalenceTable::with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquivalenceTable {
+    factors: [f64; 4],
+}
+
+impl Default for EquivalenceTable {
+    fn default() -> Self {
+        Self {
+            factors: [100.0 / 30.0, 100.0 / 0.3, 100.0 / 1800.0, 100.0 / 180.0],
+        }
+    }
+}
+
+impl EquivalenceTable {
+    /// The defaults, overridden by each row of `records`; drugs without a
+    /// row keep their default.
+    pub fn with(records: &[EquivalenceRecord]) -> Self {
+        let mut table = Self::default();
+        for r in records {
+            table.factors[Self::slot(r.drug)] = r.factor;
+        }
+        table
+    }
+
+    fn slot(drug: Drug) -> usize {
+        match drug {
+            Drug::Midodrine => 0,
+            Drug::Fludrocortisone => 1,
+            Drug::Droxidopa => 2,
+            Drug::Pyridostigmine => 3,
+        }
+    }
+
+    pub fn factor(&self, drug: Drug) -> f64 {
+        self.factors[Self::slot(drug)]
+    }
+
+    /// A prescription's daily dose in ME.
+    pub fn meq(&self, prescription: &PrescriptionRecord) -> f64 {
+        prescription.daily_mg() * self.factor(prescription.drug)
+    }
+}
+
+/// Each prescribed patient's total daily MEQ before and after, in MRN order,
+/// as the MEQ export would hold it. A phase without prescriptions is 0 ME.
+pub fn compute_meq(
+    prescriptions: &[PrescriptionRecord],
+    table: &EquivalenceTable,
+) -> Vec<MeqRecord> {
+    let mut totals: BTreeMap<&str, [f64; 2]> = BTreeMap::new();
+    for p in prescriptions {
+        totals.entry(p.mrn.as_str()).or_default()[usize::from(p.phase == Phase::Post)] +=
+            table.meq(p);
+    }
+    totals
+        .into_iter()
+        .map(|(mrn, [pre, post])| MeqRecord {
+            mrn: mrn.to_string(),
+            pre: pre as f32,
+            post: post as f32,
+            percent_reduction: None,
+        })
+        .collect()
+}
+
+/// A stored MEQ that the prescriptions do not reproduce.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeqDiscrepancy {
+    pub mrn: String,
+    pub phase: Phase,
+    pub stored: f64,
+    pub computed: f64,
+}
+
+impl MeqDiscrepancy {
+    /// Computed minus stored.
+    pub fn difference(&self) -> f64 {
+        self.computed - self.stored
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeqCheck {
+    pub tolerance: f64,
+    /// Patients with both a stored MEQ and prescriptions.
+    pub checked: usize,
+    pub discrepancies: Vec<MeqDiscrepancy>,
+    /// With a stored MEQ but no prescriptions.
+    pub only_stored: Vec<String>,
+    /// With prescriptions but no stored MEQ.
+    pub only_prescribed: Vec<String>,
+}
+
+impl MeqCheck {
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+            && self.only_stored.is_empty()
+            && self.only_prescribed.is_empty()
+    }
+
+    /// Patients with at least one discrepancy.
+    pub fn patients_differing(&self) -> usize {
+        self.discrepancies
+            .iter()
+            .map(|d| d.mrn.as_str())
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
+/// Compares every stored MEQ with the one computed from the prescriptions.
+pub fn check_meq(stored: &[MeqRecord], computed: &[MeqRecord], tolerance: f64) -> MeqCheck {
+    let by_mrn: BTreeMap<&str, &MeqRecord> = computed.iter().map(|r| (r.mrn.as_str(), r)).collect();
+    let mut report = MeqCheck {
+        tolerance,
+        checked: 0,
+        discrepancies: Vec::new(),
+        only_stored: Vec::new(),
+        only_prescribed: Vec::new(),
+    };
+    let mut seen = BTreeSet::new();
+    for row in stored {
+        seen.insert(row.mrn.as_str());
+        let Some(computed) = by_mrn.get(row.mrn.as_str()) else {
+            report.only_stored.push(row.mrn.clone());
+            continue;
+        };
+        report.checked += 1;
+        for (phase, stored, computed) in [
+            (Phase::Pre, row.pre, computed.pre),
+            (Phase::Post, row.post, computed.post),
+        ] {
+            let (stored, computed) = (f64::from(stored), f64::from(computed));
+            if (stored - computed).abs() > tolerance {
+                report.discrepancies.push(MeqDiscrepancy {
+                    mrn: row.mrn.clone(),
+                    phase,
+                    stored,
+                    computed,
+                });
+            }
+        }
+    }
+    report.only_prescribed = by_mrn
+        .keys()
+        .filter(|mrn| !seen.contains(*mrn))
+        .map(|mrn| mrn.to_string())
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meq(pre: f32, post: f32) -> MeqRecord {
+        MeqRecord {
+            mrn: "SU001".to_string(),
+            pre,
+            post,
+            percent_reduction: None,
+        }
+    }
+
+    #[test]
+    fn response_boundaries() {
+        let classify = |pre, post| MeqResponse::classify(&meq(pre, post));
+        assert_eq!(classify(10.0, 0.0), MeqResponse::Discontinued);
+        assert_eq!(classify(10.0, 5.0), MeqResponse::Halved);
+        assert_eq!(classify(10.0, 5.5), MeqResponse::Reduced);
+        assert_eq!(classify(10.0, 10.0), MeqResponse::Unchanged);
+        assert_eq!(classify(10.0, 12.0), MeqResponse::Increased);
+        assert_eq!(classify(0.0, 5.0), MeqResponse::Started);
+        assert_eq!(classify(0.0, 0.0), MeqResponse::Unchanged);
+    }
+
+    #[test]
+    fn exported_reduction_wins_unless_infinite() {
+        let mut record = meq(10.0, 6.0);
+        record.percent_reduction = Some(50.0);
+        assert_eq!(MeqResponse::classify(&record), MeqResponse::Halved);
+        let mut record = meq(0.0, 5.0);
+        record.percent_reduction = Some(f32::NEG_INFINITY);
+        assert_eq!(record.percent_reduction(), None);
+        assert_eq!(MeqResponse::classify(&record), MeqResponse::Started);
+    }
+
+    fn prescription(drug: Drug, dose: f64, per_day: f64) -> PrescriptionRecord {
+        PrescriptionRecord {
+            mrn: "SU001".to_string(),
+            phase: Phase::Pre,
+            drug,
+            dose,
+            per_day,
+        }
+    }
+
+    #[test]
+    fn a_maximum_daily_dose_is_100_me() {
+        let table = EquivalenceTable::default();
+        for (drug, dose, per_day) in [
+            (Drug::Midodrine, 10.0, 3.0),
+            (Drug::Fludrocortisone, 0.1, 3.0),
+            (Drug::Droxidopa, 600.0, 3.0),
+            (Drug::Pyridostigmine, 60.0, 3.0),
+        ] {
+            let me = table.meq(&prescription(drug, dose, per_day));
+            assert!((me - 100.0).abs() < 1e-9, "{drug:?}: {me} ME");
+        }
+    }
+
+    #[test]
+    fn daily_doses_add_up_per_phase() {
+        let computed = compute_meq(
+            &[
+                prescription(Drug::Midodrine, 10.0, 3.0),
+                prescription(Drug::Fludrocortisone, 0.1, 1.0),
+            ],
+            &EquivalenceTable::default(),
+        );
+        assert_eq!(computed.len(), 1);
+        assert!((computed[0].pre - 133.333).abs() < 1e-3);
+        assert_eq!(computed[0].post, 0.0);
+    }
+
+    #[test]
+    fn tally_counts_responders() {
+        let counts = ResponseCounts::tally(&[meq(10.0, 0.0), meq(10.0, 4.0), meq(10.0, 9.0)]);
+        assert_eq!(counts.responders(), 2);
+        assert_eq!(counts.reduced, 1);
+        assert_eq!(counts.total(), 3);
+    }
+}
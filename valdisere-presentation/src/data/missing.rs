@@ -0,0 +1,388 @@
+//! Missing data, analysis by analysis.
+//!
+//! A blank cell is not a rejected row: a reading without a heart rate still
+//! has a usable pressure, so the loader keeps it and each analysis uses the
+//! patients that have what it needs. This module lists, per analysis, the
+//! patients it leaves out or only partly covers and what they lack, so every
+//! n on the slides can be accounted for. Rows the loader rejected and values
+//! it cleared are counted against their patient too.
+//!
+//! Each analysis names its base, the patients it is counted out of: the
+//! hemodynamic analyses share [`BP_PATIENTS`] and the outcome analyses
+//! [`ALL_PATIENTS`].
+
+use crate::data::hemodynamics::{orthostatic_drops, paired_drops, DropStats, PatientDrop};
+use crate::data::ingest::LoadReport;
+use crate::data::outcomes::{join, PatientOutcome};
+use crate::data::records::{BpRecord, CgiRecord, MeqRecord, Phase};
+use crate::data::visits::Aggregation;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One patient's gap in one analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingEntry {
+    pub mrn: String,
+    /// What is missing, e.g. "no stand test after the procedure".
+    pub what: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Missingness {
+    pub analysis: &'static str,
+    /// Who the analysis starts from, e.g. [`BP_PATIENTS`].
+    pub base: &'static str,
+    /// How many patients that is.
+    pub patients: usize,
+    /// Those of them with something missing, in MRN order.
+    pub missing: Vec<MissingEntry>,
+}
+
+impl Missingness {
+    /// Patients without gaps.
+    pub fn complete(&self) -> usize {
+        self.patients - self.missing.len()
+    }
+}
+
+/// The base of the hemodynamic analyses: every patient in the readings
+/// export, including those whose rows were all rejected.
+pub const BP_PATIENTS: &str = "patients in the BP export";
+
+/// The base of the outcome analyses: every patient with an accepted row in
+/// any of the three exports.
+pub const ALL_PATIENTS: &str = "patients in any export";
+
+/// The readings columns that can be blank, in [`BpRecord`] order.
+const COLUMNS: [&str; 3] = ["Systolic", "Diastolic", "HEART_RATE"];
+
+/// One patient's rows in the readings export.
+#[derive(Default)]
+struct RowGaps {
+    /// Accepted rows.
+    readings: usize,
+    rejected: usize,
+    /// Per column, cells left blank in the export and cells cleared on load.
+    blank: [usize; 3],
+    cleared: [usize; 3],
+}
+
+impl RowGaps {
+    /// What was lost, or `None` if every row and cell was usable.
+    fn describe(&self) -> Option<String> {
+        let mut what = Vec::new();
+        if self.rejected > 0 {
+            what.push(format!(
+                "{} of {} rows rejected",
+                self.rejected,
+                self.rejected + self.readings
+            ));
+        }
+        for (i, column) in COLUMNS.iter().enumerate() {
+            let readings = self.readings;
+            if self.blank[i] > 0 {
+                what.push(format!(
+                    "{column} blank in {} of {readings} readings",
+                    self.blank[i]
+                ));
+            }
+            if self.cleared[i] > 0 {
+                what.push(format!(
+                    "{column} out of range in {} of {readings} readings",
+                    self.cleared[i]
+                ));
+            }
+        }
+        (!what.is_empty()).then(|| what.join(", "))
+    }
+}
+
+/// "before", "after" or "before or after" the procedure, for the phases
+/// that lack something; `None` if neither does.
+fn lacking(pre: bool, post: bool) -> Option<&'static str> {
+    match (pre, post) {
+        (true, true) => None,
+        (false, true) => Some("before the procedure"),
+        (true, false) => Some("after the procedure"),
+        (false, false) => Some("before or after the procedure"),
+    }
+}
+
+/// Rejected rows, cleared values and blank cells in the readings, and the
+/// patients each hemodynamic analysis leaves out. All four start from every
+/// patient in the export, counting those whose rows were all rejected; a
+/// rejected row without an ID belongs to no patient and is only listed in
+/// the load report. Whether a patient has a value does not depend on the
+/// aggregation rule, so none is taken.
+pub fn bp_missingness(report: &LoadReport<BpRecord>) -> Vec<Missingness> {
+    let records = &report.records;
+    let mut rows: BTreeMap<&str, RowGaps> = BTreeMap::new();
+    for r in records {
+        let gaps = rows.entry(r.mrn.as_str()).or_default();
+        gaps.readings += 1;
+        for (count, value) in gaps.blank.iter_mut().zip([r.sys, r.dia, r.hr]) {
+            *count += usize::from(value.is_none());
+        }
+    }
+    for row in &report.rejected {
+        if let Some(mrn) = &row.mrn {
+            rows.entry(mrn.as_str()).or_default().rejected += 1;
+        }
+    }
+    for value in &report.cleared {
+        let column = value.reason.split(':').next().unwrap_or_default();
+        if let (Some(mrn), Some(i)) = (&value.mrn, COLUMNS.iter().position(|&c| c == column)) {
+            let gaps = rows.entry(mrn.as_str()).or_default();
+            gaps.cleared[i] += 1;
+            gaps.blank[i] -= 1;
+        }
+    }
+    let cells = rows
+        .iter()
+        .filter_map(|(&mrn, gaps)| {
+            Some(MissingEntry {
+                mrn: mrn.to_string(),
+                what: gaps.describe()?,
+            })
+        })
+        .collect();
+
+    let drops = orthostatic_drops(records);
+    // (MRN, post) for every patient and phase with a stand test.
+    let tested: BTreeSet<(&str, bool)> = drops
+        .iter()
+        .map(|d| (d.mrn.as_str(), d.phase == Phase::Post))
+        .collect();
+    let untested = |mrn: &str| -> Option<String> {
+        let side = lacking(
+            tested.contains(&(mrn, false)),
+            tested.contains(&(mrn, true)),
+        )?;
+        Some(format!("no stand test {side}"))
+    };
+
+    let patients: BTreeMap<String, PatientDrop> = paired_drops(&drops)
+        .into_iter()
+        .map(|p| (p.pre.mrn.clone(), p))
+        .collect();
+    // A patient without stand tests on both sides lacks the drop where the
+    // tests are missing; one with both lacks it where no test had both values.
+    let gaps = |value: fn(&DropStats) -> Option<f64>, what: &str| -> Vec<MissingEntry> {
+        rows.keys()
+            .filter_map(|&mrn| {
+                let what = match patients.get(mrn) {
+                    Some(p) => format!(
+                        "{what} {}",
+                        lacking(value(&p.pre).is_some(), value(&p.post).is_some())?
+                    ),
+                    None => untested(mrn)?,
+                };
+                Some(MissingEntry {
+                    mrn: mrn.to_string(),
+                    what,
+                })
+            })
+            .collect()
+    };
+
+    let analysis = |analysis, missing| Missingness {
+        analysis,
+        base: BP_PATIENTS,
+        patients: rows.len(),
+        missing,
+    };
+    vec![
+        analysis("BP readings", cells),
+        analysis(
+            "Systolic drop",
+            rows.keys()
+                .filter_map(|&mrn| {
+                    Some(MissingEntry {
+                        mrn: mrn.to_string(),
+                        what: untested(mrn)?,
+                    })
+                })
+                .collect(),
+        ),
+        analysis(
+            "Diastolic drop",
+            gaps(
+                |s| s.diastolic(Aggregation::Mean),
+                "no stand test with both diastolic values",
+            ),
+        ),
+        analysis(
+            "HR increment",
+            gaps(
+                |s| s.hr_increment(Aggregation::Mean),
+                "no stand test with both heart rates",
+            ),
+        ),
+    ]
+}
+
+/// The patients in any of the three exports who lack an MEQ record, a CGI-I
+/// score, or either side of the systolic improvement vs MEQ reduction
+/// correlation.
+pub fn outcome_missingness(
+    bp: &[BpRecord],
+    meq: &[MeqRecord],
+    cgi: &[CgiRecord],
+) -> Vec<Missingness> {
+    let everyone: BTreeSet<&str> = bp
+        .iter()
+        .map(|r| r.mrn.as_str())
+        .chain(meq.iter().map(|r| r.mrn.as_str()))
+        .chain(cgi.iter().map(|r| r.mrn.as_str()))
+        .collect();
+    let absent = |present: &BTreeSet<&str>, what: &str| -> Vec<MissingEntry> {
+        everyone
+            .iter()
+            .filter(|mrn| !present.contains(*mrn))
+            .map(|mrn| MissingEntry {
+                mrn: mrn.to_string(),
+                what: what.to_string(),
+            })
+            .collect()
+    };
+
+    let with_meq: BTreeSet<&str> = meq.iter().map(|r| r.mrn.as_str()).collect();
+    let patients = paired_drops(&orthostatic_drops(bp));
+    let outcomes: BTreeMap<String, PatientOutcome> = join(&patients, meq, cgi, Aggregation::Mean)
+        .into_iter()
+        .map(|o| (o.mrn.clone(), o))
+        .collect();
+    let uncorrelated = everyone
+        .iter()
+        .filter_map(|&mrn| {
+            let outcome = outcomes.get(mrn);
+            let mut what = Vec::new();
+            if outcome.and_then(|o| o.sys_improvement).is_none() {
+                what.push("no stand tests before and after");
+            }
+            if outcome.and_then(|o| o.meq_reduction).is_none() {
+                what.push(if with_meq.contains(mrn) {
+                    "no MEQ before the procedure to reduce"
+                } else {
+                    "no MEQ record"
+                });
+            }
+            (!what.is_empty()).then(|| MissingEntry {
+                mrn: mrn.to_string(),
+                what: what.join(", "),
+            })
+        })
+        .collect();
+
+    vec![
+        Missingness {
+            analysis: "MEQ",
+            base: ALL_PATIENTS,
+            patients: everyone.len(),
+            missing: absent(&with_meq, "no MEQ record"),
+        },
+        Missingness {
+            analysis: "CGI-I",
+            base: ALL_PATIENTS,
+            patients: everyone.len(),
+            missing: absent(
+                &cgi.iter().map(|r| r.mrn.as_str()).collect(),
+                "no CGI-I score",
+            ),
+        },
+        Missingness {
+            analysis: "Correlation",
+            base: ALL_PATIENTS,
+            patients: everyone.len(),
+            missing: uncorrelated,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ingest::load_csv;
+
+    const HEADER: &str =
+        "PAT_MRN_ID,RECORDED_TIME,Systolic,Diastolic,HEART_RATE,BP_POSITION,pre or post procedure";
+
+    /// SU001 has a stand test on each side, the standing heart rate after
+    /// the procedure cleared as impossible. SU002 has one before only, its
+    /// standing diastolic blank. SU003's only row is rejected, as is a row
+    /// without an ID.
+    fn report() -> LoadReport<BpRecord> {
+        let rows = [
+            "SU001,2025-03-01 10:00:00,120,80,70,Lying,pre",
+            "SU001,2025-03-01 10:03:00,100,70,90,Standing,pre",
+            "SU001,2025-06-01 10:00:00,120,80,70,Lying,post",
+            "SU001,2025-06-01 10:03:00,110,75,0,Standing,post",
+            "SU002,2025-03-02 10:00:00,130,85,65,Lying,pre",
+            "SU002,2025-03-02 10:03:00,110,,85,Standing,pre",
+            "SU003,2025-03-03 10:00:00,400,85,70,Lying,pre",
+            ",2025-03-03 10:03:00,120,80,70,Standing,pre",
+        ];
+        load_csv(&format!("{HEADER}\n{}\n", rows.join("\n"))).unwrap()
+    }
+
+    fn entries(a: &Missingness) -> Vec<(&str, &str)> {
+        a.missing
+            .iter()
+            .map(|e| (e.mrn.as_str(), e.what.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn hemodynamic_analyses_share_one_base() {
+        let analyses = bp_missingness(&report());
+        for a in &analyses {
+            assert_eq!((a.base, a.patients), (BP_PATIENTS, 3), "{}", a.analysis);
+        }
+        let [readings, systolic, diastolic, hr] = &analyses[..] else {
+            panic!("{analyses:?}");
+        };
+        assert_eq!(
+            entries(readings),
+            [
+                ("SU001", "HEART_RATE out of range in 1 of 4 readings"),
+                ("SU002", "Diastolic blank in 1 of 2 readings"),
+                ("SU003", "1 of 1 rows rejected"),
+            ]
+        );
+        assert_eq!(
+            entries(systolic),
+            [
+                ("SU002", "no stand test after the procedure"),
+                ("SU003", "no stand test before or after the procedure"),
+            ]
+        );
+        assert_eq!(
+            entries(diastolic),
+            [
+                ("SU002", "no stand test after the procedure"),
+                ("SU003", "no stand test before or after the procedure"),
+            ]
+        );
+        assert_eq!(
+            entries(hr),
+            [
+                (
+                    "SU001",
+                    "no stand test with both heart rates after the procedure"
+                ),
+                ("SU002", "no stand test after the procedure"),
+                ("SU003", "no stand test before or after the procedure"),
+            ]
+        );
+        assert_eq!(hr.complete(), 0);
+    }
+
+    #[test]
+    fn outcome_analyses_count_every_export() {
+        let report = report();
+        let analyses = outcome_missingness(&report.records, &[], &[]);
+        for a in &analyses {
+            assert_eq!((a.base, a.patients), (ALL_PATIENTS, 2), "{}", a.analysis);
+            assert_eq!(a.complete(), 0, "{}", a.analysis);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+pub mod cgi;
+pub mod cohort;
+pub mod consistency;
+pub mod datasets;
+pub mod hemodynamics;
+pub mod ingest;
+pub mod medication;
+pub mod missing;
+pub mod mrn;
+pub mod orthostatic;
+pub mod outcomes;
+pub mod positional;
+pub mod records;
+pub mod stats;
+pub mod timepoints;
+pub mod visits;
+pub mod workbook;
@@ -0,0 +1,54 @@
+//! Recognising hospital MRNs: an `E` followed by six to eight digits,
+//! standing alone. Other identifiers, such as names or dates of birth, have
+//! no fixed shape and are not recognised.
+//!
+//! The build uses this to keep MRNs out of the compiled deck, `standup-scan`
+//! to check what `dx` wrote, and the app to refuse a data file that was not
+//! de-identified.
+
+/// Length of the MRN starting at byte `i`, if one does.
+fn mrn_at(bytes: &[u8], i: usize) -> Option<usize> {
+    let standalone = |b: Option<&u8>| b.is_none_or(|b| !b.is_ascii_alphanumeric());
+    if bytes[i] != b'E' || (i > 0 && !standalone(bytes.get(i - 1))) {
+        return None;
+    }
+    let digits = bytes[i + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    ((6..=8).contains(&digits) && standalone(bytes.get(i + 1 + digits))).then_some(1 + digits)
+}
+
+pub fn is_mrn(value: &str) -> bool {
+    !value.is_empty() && mrn_at(value.as_bytes(), 0) == Some(value.len())
+}
+
+/// Byte offset of the first MRN-shaped string in `bytes`.
+pub fn first_mrn(bytes: &[u8]) -> Option<usize> {
+    (0..bytes.len()).find(|&i| mrn_at(bytes, i).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spelled out here, the examples would fail the build's own check.
+    fn e(rest: &str) -> String {
+        format!("E{rest}")
+    }
+
+    #[test]
+    fn six_to_eight_digits_standing_alone() {
+        assert!(is_mrn(&e("123456")));
+        assert!(is_mrn(&e("12345678")));
+        assert!(!is_mrn(&e("12345")));
+        assert!(!is_mrn(&e("123456789")));
+        assert!(!is_mrn("SU001"));
+        assert_eq!(
+            first_mrn(format!("id,{},x", e("1234567")).as_bytes()),
+            Some(3)
+        );
+        assert_eq!(first_mrn(format!("X{}", e("1234567")).as_bytes()), None);
+        assert_eq!(first_mrn(e("1234567a").as_bytes()), None);
+    }
+}
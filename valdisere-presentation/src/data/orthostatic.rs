@@ -0,0 +1,396 @@
+//! Consensus classification of orthostatic hypotension (OH).
+//!
+//! OH is a sustained fall of at least 20 mmHg systolic or 10 mmHg diastolic
+//! within 3 minutes of standing, measured against the supine reading. Each
+//! measurement session is classified on its own; a patient's status for a
+//! visit, and for a phase, is then derived from the sessions in it.
+
+use crate::data::records::{BpRecord, Phase, Position};
+use crate::data::visits::visits;
+use chrono::{NaiveDateTime, TimeDelta};
+use std::collections::BTreeMap;
+
+pub const SYSTOLIC_THRESHOLD: i32 = 20;
+pub const DIASTOLIC_THRESHOLD: i32 = 10;
+/// A standing reading must follow the posture change by at most this long.
+const STANDING_WINDOW_MINUTES: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OhStatus {
+    Oh,
+    NoOh,
+    /// No supine baseline, no standing reading within the window, or values
+    /// missing in a way that leaves the criterion undecided.
+    Indeterminate,
+}
+
+impl OhStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OhStatus::Oh => "OH",
+            OhStatus::NoOh => "No OH",
+            OhStatus::Indeterminate => "Indeterminate",
+        }
+    }
+
+    pub fn is_determinate(&self) -> bool {
+        *self != OhStatus::Indeterminate
+    }
+}
+
+/// One stand test, as [`Visit::stand_tests`](crate::data::visits::Visit::stand_tests)
+/// splits a visit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session<'a> {
+    pub mrn: &'a str,
+    pub phase: Phase,
+    /// Which of the patient's visits in this phase it belongs to.
+    pub visit: usize,
+    pub start: NaiveDateTime,
+    /// In `RECORDED_TIME` order.
+    pub readings: Vec<&'a BpRecord>,
+}
+
+/// Splits each patient's readings, per phase, into sessions.
+pub fn sessions(records: &[BpRecord]) -> Vec<Session<'_>> {
+    let mut sessions = Vec::new();
+    for visit in visits(records) {
+        for test in visit.stand_tests() {
+            sessions.push(Session {
+                mrn: visit.mrn,
+                phase: visit.phase,
+                visit: visit.index,
+                start: test[0].time,
+                readings: test.to_vec(),
+            });
+        }
+    }
+    sessions
+}
+
+impl Session<'_> {
+    /// Applies the consensus criterion. The baseline is the latest supine
+    /// reading; a standing reading qualifies when it was taken within the
+    /// window of the first standing reading of its run, which is when the
+    /// patient stood.
+    pub fn classify(&self) -> OhStatus {
+        let window = TimeDelta::minutes(STANDING_WINDOW_MINUTES);
+        let mut baseline: Option<&BpRecord> = None;
+        let mut stood_at: Option<NaiveDateTime> = None;
+        let mut decided_no = false;
+        for r in &self.readings {
+            if r.pos == Position::Lying && r.sys.is_some() {
+                baseline = Some(r);
+            }
+            if r.pos != Position::Standing {
+                stood_at = None;
+                continue;
+            }
+            if r.time - *stood_at.get_or_insert(r.time) > window {
+                continue;
+            }
+            let Some(base) = baseline else { continue };
+            let sys_drop = base.sys.zip(r.sys).map(|(l, s)| l - s);
+            let dia_drop = base.dia.zip(r.dia).map(|(l, s)| l - s);
+            if sys_drop.is_some_and(|d| d >= SYSTOLIC_THRESHOLD)
+                || dia_drop.is_some_and(|d| d >= DIASTOLIC_THRESHOLD)
+            {
+                return OhStatus::Oh;
+            }
+            // Ruling OH out needs both components below threshold.
+            if sys_drop.is_some() && dia_drop.is_some() {
+                decided_no = true;
+            }
+        }
+        if decided_no {
+            OhStatus::NoOh
+        } else {
+            OhStatus::Indeterminate
+        }
+    }
+}
+
+/// One patient-visit and its status, which is OH if any of its sessions
+/// is, no OH if at least one session rules it out and none shows it, and
+/// indeterminate otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisitOh<'a> {
+    pub mrn: &'a str,
+    pub phase: Phase,
+    /// Which of the patient's visits in this phase, counting from 0.
+    pub visit: usize,
+    pub start: NaiveDateTime,
+    pub status: OhStatus,
+}
+
+/// Labels each patient-visit; ordered like the sessions, by patient, phase
+/// and time.
+pub fn visit_statuses<'a>(sessions: &[Session<'a>]) -> Vec<VisitOh<'a>> {
+    sessions
+        .chunk_by(|a, b| (a.mrn, a.phase, a.visit) == (b.mrn, b.phase, b.visit))
+        .map(|visit| VisitOh {
+            mrn: visit[0].mrn,
+            phase: visit[0].phase,
+            visit: visit[0].visit,
+            start: visit[0].start,
+            status: combine(visit.iter().map(Session::classify)),
+        })
+        .collect()
+}
+
+/// A patient's status before and after the procedure, combined from their
+/// visits in each phase the same way a visit's is from its sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatientOh {
+    pub pre: OhStatus,
+    pub post: OhStatus,
+}
+
+/// How a patient's status moved across the procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Transition {
+    Resolved,
+    Persisted,
+    New,
+    Never,
+}
+
+impl Transition {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transition::Resolved => "Resolved",
+            Transition::Persisted => "Persisted",
+            Transition::New => "New",
+            Transition::Never => "Never",
+        }
+    }
+}
+
+impl PatientOh {
+    /// Only defined when both phases are determinate.
+    pub fn transition(&self) -> Option<Transition> {
+        match (self.pre, self.post) {
+            (OhStatus::Oh, OhStatus::NoOh) => Some(Transition::Resolved),
+            (OhStatus::Oh, OhStatus::Oh) => Some(Transition::Persisted),
+            (OhStatus::NoOh, OhStatus::Oh) => Some(Transition::New),
+            (OhStatus::NoOh, OhStatus::NoOh) => Some(Transition::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Relies on the variant order: any OH wins, then any no-OH.
+fn combine(statuses: impl Iterator<Item = OhStatus>) -> OhStatus {
+    statuses.min().unwrap_or(OhStatus::Indeterminate)
+}
+
+/// How many sessions, visits or patients fall in each status.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StatusCounts {
+    pub oh: usize,
+    pub determinate: usize,
+    pub indeterminate: usize,
+}
+
+impl StatusCounts {
+    fn add(&mut self, status: OhStatus) {
+        match status {
+            OhStatus::Oh => {
+                self.oh += 1;
+                self.determinate += 1;
+            }
+            OhStatus::NoOh => self.determinate += 1,
+            OhStatus::Indeterminate => self.indeterminate += 1,
+        }
+    }
+
+    /// Share of those with a determinate status who have OH.
+    pub fn rate(&self) -> Option<f64> {
+        (self.determinate > 0).then(|| self.oh as f64 / self.determinate as f64)
+    }
+}
+
+/// Status counts for one phase, per session, per visit and per patient.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Prevalence {
+    pub sessions: StatusCounts,
+    pub visits: StatusCounts,
+    pub patients: StatusCounts,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OhSummary {
+    pub pre: Prevalence,
+    pub post: Prevalence,
+    /// Patients with sessions in both phases, in MRN order.
+    pub patients: Vec<PatientOh>,
+}
+
+impl OhSummary {
+    pub fn build(records: &[BpRecord]) -> Self {
+        let sessions = sessions(records);
+        let mut summary = Self::default();
+        for session in &sessions {
+            summary
+                .phase_mut(session.phase)
+                .sessions
+                .add(session.classify());
+        }
+
+        let mut by_patient: BTreeMap<&str, (Vec<OhStatus>, Vec<OhStatus>)> = BTreeMap::new();
+        for visit in visit_statuses(&sessions) {
+            summary.phase_mut(visit.phase).visits.add(visit.status);
+            let (pre, post) = by_patient.entry(visit.mrn).or_default();
+            match visit.phase {
+                Phase::Pre => pre.push(visit.status),
+                Phase::Post => post.push(visit.status),
+            }
+        }
+
+        for (pre, post) in by_patient.into_values() {
+            for (statuses, prevalence) in [(&pre, &mut summary.pre), (&post, &mut summary.post)] {
+                if !statuses.is_empty() {
+                    prevalence.patients.add(combine(statuses.iter().copied()));
+                }
+            }
+            if !pre.is_empty() && !post.is_empty() {
+                summary.patients.push(PatientOh {
+                    pre: combine(pre.into_iter()),
+                    post: combine(post.into_iter()),
+                });
+            }
+        }
+        summary
+    }
+
+    fn phase_mut(&mut self, phase: Phase) -> &mut Prevalence {
+        match phase {
+            Phase::Pre => &mut self.pre,
+            Phase::Post => &mut self.post,
+        }
+    }
+
+    pub fn transitions(&self, transition: Transition) -> usize {
+        self.patients
+            .iter()
+            .filter(|p| p.transition() == Some(transition))
+            .count()
+    }
+
+    /// Patients seen in both phases whose status is undecided in either.
+    pub fn unpaired(&self) -> usize {
+        self.patients
+            .iter()
+            .filter(|p| p.transition().is_none())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(time: &str, pos: Position, sys: i32, dia: i32) -> BpRecord {
+        BpRecord {
+            mrn: "SU001".to_string(),
+            time: NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap(),
+            sys: Some(sys),
+            dia: Some(dia),
+            hr: Some(70),
+            pos,
+            phase: Phase::Pre,
+        }
+    }
+
+    /// One stand test from a lying reading of 140/80 to a standing one
+    /// `minutes` later.
+    fn stand_test(minutes: u32, sys: Option<i32>, dia: Option<i32>) -> OhStatus {
+        let mut standing = reading(
+            &format!("2025-03-01 09:{minutes:02}"),
+            Position::Standing,
+            0,
+            0,
+        );
+        (standing.sys, standing.dia) = (sys, dia);
+        let records = [
+            reading("2025-03-01 09:00", Position::Lying, 140, 80),
+            standing,
+        ];
+        sessions(&records)[0].classify()
+    }
+
+    #[test]
+    fn consensus_thresholds_are_inclusive() {
+        assert_eq!(stand_test(1, Some(120), Some(80)), OhStatus::Oh);
+        assert_eq!(stand_test(1, Some(121), Some(71)), OhStatus::NoOh);
+        assert_eq!(stand_test(1, Some(140), Some(70)), OhStatus::Oh);
+        // A rise is not a drop.
+        assert_eq!(stand_test(1, Some(160), Some(90)), OhStatus::NoOh);
+    }
+
+    #[test]
+    fn a_missing_component_can_show_oh_but_not_rule_it_out() {
+        assert_eq!(stand_test(1, Some(115), None), OhStatus::Oh);
+        assert_eq!(stand_test(1, Some(139), None), OhStatus::Indeterminate);
+        assert_eq!(stand_test(1, None, Some(79)), OhStatus::Indeterminate);
+    }
+
+    #[test]
+    fn without_a_supine_baseline_a_session_is_indeterminate() {
+        let records = [
+            reading("2025-03-01 09:00", Position::Sitting, 140, 80),
+            reading("2025-03-01 09:01", Position::Standing, 100, 60),
+        ];
+        assert_eq!(sessions(&records)[0].classify(), OhStatus::Indeterminate);
+    }
+
+    #[test]
+    fn window_includes_its_last_minute() {
+        let records = [
+            reading("2025-03-01 09:00", Position::Lying, 140, 80),
+            reading("2025-03-01 09:01", Position::Standing, 138, 79),
+            reading("2025-03-01 09:04", Position::Standing, 110, 70),
+        ];
+        assert_eq!(sessions(&records)[0].classify(), OhStatus::Oh);
+    }
+
+    #[test]
+    fn window_runs_from_the_first_standing_reading() {
+        // Each standing reading is 2 minutes after the one before, but the
+        // last is 4 minutes after the patient stood.
+        let records = [
+            reading("2025-03-01 09:00", Position::Lying, 140, 80),
+            reading("2025-03-01 09:02", Position::Standing, 130, 78),
+            reading("2025-03-01 09:04", Position::Standing, 128, 77),
+            reading("2025-03-01 09:06", Position::Standing, 100, 60),
+        ];
+        let sessions = sessions(&records);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].classify(), OhStatus::NoOh);
+    }
+
+    #[test]
+    fn each_patient_visit_is_labelled() {
+        let records = [
+            // Two stand tests on the first day, one showing OH.
+            reading("2025-03-01 09:00", Position::Lying, 140, 80),
+            reading("2025-03-01 09:02", Position::Standing, 135, 78),
+            reading("2025-03-01 11:00", Position::Lying, 140, 80),
+            reading("2025-03-01 11:02", Position::Standing, 110, 75),
+            // One the next day without.
+            reading("2025-03-02 09:00", Position::Lying, 140, 80),
+            reading("2025-03-02 09:02", Position::Standing, 138, 79),
+        ];
+        let sessions = sessions(&records);
+        let visits = visit_statuses(&sessions);
+        let labels: Vec<(usize, OhStatus)> = visits.iter().map(|v| (v.visit, v.status)).collect();
+        assert_eq!(labels, [(0, OhStatus::Oh), (1, OhStatus::NoOh)]);
+
+        let summary = OhSummary::build(&records);
+        assert_eq!(summary.pre.sessions.oh, 1);
+        assert_eq!(summary.pre.sessions.determinate, 3);
+        assert_eq!(summary.pre.visits.oh, 1);
+        assert_eq!(summary.pre.visits.determinate, 2);
+        assert_eq!(summary.pre.patients.oh, 1);
+    }
+}
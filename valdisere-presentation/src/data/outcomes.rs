@@ -0,0 +1,80 @@
+//! Each patient's hemodynamic, medication and clinical outcome side by side,
+//! joined on MRN, so the three can be correlated.
+//!
+//! Both numeric outcomes are signed so that positive is better: the systolic
+//! improvement is the pre drop minus the post drop, and the MEQ reduction is
+//! the percentage the dose came down.
+
+use crate::data::hemodynamics::PatientDrop;
+use crate::data::records::{CgiRecord, MeqRecord};
+use crate::data::stats::{spearman, LinearFit, TestResult};
+use crate::data::visits::Aggregation;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatientOutcome {
+    pub mrn: String,
+    /// Pre minus post orthostatic systolic drop, in mmHg.
+    pub sys_improvement: Option<f64>,
+    /// See [`MeqRecord::percent_reduction`].
+    pub meq_reduction: Option<f64>,
+    pub cgi: Option<i32>,
+}
+
+/// Every patient in any of the three sources, in MRN order. The systolic
+/// improvement needs stand tests in both phases under `rule`.
+pub fn join(
+    patients: &[PatientDrop],
+    meq: &[MeqRecord],
+    cgi: &[CgiRecord],
+    rule: Aggregation,
+) -> Vec<PatientOutcome> {
+    let mut outcomes: BTreeMap<&str, PatientOutcome> = BTreeMap::new();
+    for p in patients {
+        let improvement = p
+            .pre
+            .systolic(rule)
+            .zip(p.post.systolic(rule))
+            .map(|(pre, post)| pre - post);
+        entry(&mut outcomes, &p.pre.mrn).sys_improvement = improvement;
+    }
+    for r in meq {
+        entry(&mut outcomes, &r.mrn).meq_reduction = r.percent_reduction();
+    }
+    for r in cgi {
+        entry(&mut outcomes, &r.mrn).cgi = Some(r.score);
+    }
+    outcomes.into_values().collect()
+}
+
+fn entry<'a, 'm>(
+    outcomes: &'m mut BTreeMap<&'a str, PatientOutcome>,
+    mrn: &'a str,
+) -> &'m mut PatientOutcome {
+    outcomes.entry(mrn).or_insert_with(|| PatientOutcome {
+        mrn: mrn.to_string(),
+        sys_improvement: None,
+        meq_reduction: None,
+        cgi: None,
+    })
+}
+
+/// How two outcomes move together, over the patients who have both.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Correlation {
+    pub n: usize,
+    /// Spearman's rho and its p-value.
+    pub spearman: Option<TestResult>,
+    pub fit: Option<LinearFit>,
+}
+
+impl Correlation {
+    pub fn of(pairs: &[(f64, f64)]) -> Self {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = pairs.iter().copied().unzip();
+        Self {
+            n: pairs.len(),
+            spearman: spearman(&xs, &ys),
+            fit: LinearFit::fit(&xs, &ys),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Per-patient vitals by phase and position: the contents of
+//! `standup_data.json`. The `standup-data` tool writes that file from the
+//! blood pressure export and the Standup1 slide reads it back, both through
+//! these types.
+
+use crate::data::records::{BpRecord, Phase, Position};
+use crate::data::visits::{visits, Aggregation, Worst};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatientData {
+    pub mrn: String,
+    pub pre: PhaseData,
+    pub post: PhaseData,
+}
+
+/// A position is `None` when the patient has no usable reading in it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseData {
+    pub lying: Option<Vitals>,
+    pub sitting: Option<Vitals>,
+    pub standing: Option<Vitals>,
+}
+
+impl PhaseData {
+    pub fn get(&self, position: Position) -> Option<&Vitals> {
+        match position {
+            Position::Lying => self.lying.as_ref(),
+            Position::Sitting => self.sitting.as_ref(),
+            Position::Standing => self.standing.as_ref(),
+        }
+    }
+}
+
+/// A patient's readings reduced by the chosen [`Aggregation`], rounded to
+/// whole units.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vitals {
+    pub sys: i32,
+    pub dia: i32,
+    pub hr: Option<i32>,
+}
+
+/// Readings collected for one patient, phase and position, by visit.
+#[derive(Default)]
+struct Readings {
+    sys: Vec<Vec<f64>>,
+    dia: Vec<Vec<f64>>,
+    hr: Vec<Vec<f64>>,
+}
+
+impl Readings {
+    fn start_visit(&mut self) {
+        self.sys.push(Vec::new());
+        self.dia.push(Vec::new());
+        self.hr.push(Vec::new());
+    }
+
+    fn push(&mut self, sys: i32, dia: i32, hr: Option<i32>) {
+        let visit = self.sys.len() - 1;
+        self.sys[visit].push(f64::from(sys));
+        self.dia[visit].push(f64::from(dia));
+        self.hr[visit].extend(hr.map(f64::from));
+    }
+
+    /// Under [`Aggregation::Worst`] each value is its own extreme: the
+    /// lowest pressures and the highest heart rate.
+    fn vitals(&self, rule: Aggregation) -> Option<Vitals> {
+        Some(Vitals {
+            sys: round(rule.apply(&self.sys, Worst::Lowest)?),
+            dia: round(rule.apply(&self.dia, Worst::Lowest)?),
+            hr: rule.apply(&self.hr, Worst::Highest).map(round),
+        })
+    }
+}
+
+/// Rounds halves to even, as the original Python pipeline did.
+fn round(value: f64) -> i32 {
+    value.round_ties_even() as i32
+}
+
+fn slot(position: Position) -> usize {
+    match position {
+        Position::Lying => 0,
+        Position::Sitting => 1,
+        Position::Standing => 2,
+    }
+}
+
+/// Reduces each patient's readings per phase and position to one set of
+/// vitals under `rule`, visit by visit. Readings need both systolic and
+/// diastolic values to count; heart rate is taken from the readings that
+/// have one. Patients are listed in order of first appearance.
+pub fn aggregate(records: &[BpRecord], rule: Aggregation) -> Vec<PatientData> {
+    let mut order: Vec<&str> = Vec::new();
+    for r in records
+        .iter()
+        .filter(|r| r.sys.is_some() && r.dia.is_some())
+    {
+        if !order.contains(&r.mrn.as_str()) {
+            order.push(&r.mrn);
+        }
+    }
+    let mut readings: BTreeMap<&str, [[Readings; 3]; 2]> = BTreeMap::new();
+    for visit in visits(records) {
+        let phase = match visit.phase {
+            Phase::Pre => 0,
+            Phase::Post => 1,
+        };
+        let positions = &mut readings.entry(visit.mrn).or_default()[phase];
+        for position in positions.iter_mut() {
+            position.start_visit();
+        }
+        for r in &visit.readings {
+            if let (Some(sys), Some(dia)) = (r.sys, r.dia) {
+                positions[slot(r.pos)].push(sys, dia, r.hr);
+            }
+        }
+    }
+
+    let phase = |positions: &[Readings; 3]| PhaseData {
+        lying: positions[0].vitals(rule),
+        sitting: positions[1].vitals(rule),
+        standing: positions[2].vitals(rule),
+    };
+    order
+        .into_iter()
+        .map(|mrn| {
+            let [pre, post] = &readings[mrn];
+            PatientData {
+                mrn: mrn.to_string(),
+                pre: phase(pre),
+                post: phase(post),
+            }
+        })
+        .collect()
+}
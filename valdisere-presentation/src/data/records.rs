@@ -0,0 +1,417 @@
+use crate::data::ingest::{optional_number, CsvRecord};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+/// Every dataset is joined on `PAT_MRN_ID`, so a row without one is unusable.
+/// In the bundled data the column holds study IDs, not MRNs; see `build.rs`.
+fn require_mrn(mrn: &str) -> Result<(), String> {
+    if mrn.is_empty() {
+        return Err("PAT_MRN_ID: missing".to_string());
+    }
+    Ok(())
+}
+
+/// `RECORDED_TIME` as exported, e.g. `2025-01-31 09:05:00`.
+fn recorded_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    NaiveDateTime::parse_from_str(&raw, "%Y-%m-%d %H:%M:%S")
+        .map_err(|_| D::Error::custom(format!("'{raw}' is not a date and time")))
+}
+
+// --- Hemodynamics Types ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Position {
+    Lying,
+    Sitting,
+    Standing,
+}
+
+impl TryFrom<String> for Position {
+    type Error = String;
+
+    // The export mixes "Lying" and "lying".
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "lying" => Ok(Position::Lying),
+            "sitting" => Ok(Position::Sitting),
+            "standing" => Ok(Position::Standing),
+            _ => Err(format!("unknown position '{value}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Phase {
+    Pre,
+    Post,
+}
+
+impl TryFrom<String> for Phase {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let lower = value.to_lowercase();
+        if lower.contains("pre") {
+            Ok(Phase::Pre)
+        } else if lower.contains("post") {
+            Ok(Phase::Post)
+        } else {
+            Err(format!("unknown phase '{value}'"))
+        }
+    }
+}
+
+/// One reading from `standup_results.csv`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BpRecord {
+    #[serde(rename = "PAT_MRN_ID")]
+    pub mrn: String,
+    #[serde(rename = "RECORDED_TIME", deserialize_with = "recorded_time")]
+    pub time: NaiveDateTime,
+    #[serde(rename = "Systolic", deserialize_with = "optional_number")]
+    pub sys: Option<i32>,
+    #[serde(rename = "Diastolic", deserialize_with = "optional_number")]
+    pub dia: Option<i32>,
+    #[serde(rename = "HEART_RATE", deserialize_with = "optional_number")]
+    pub hr: Option<i32>,
+    #[serde(rename = "BP_POSITION")]
+    pub pos: Position,
+    #[serde(rename = "pre or post procedure")]
+    pub phase: Phase,
+}
+
+impl CsvRecord for BpRecord {
+    const DATASET: &'static str = "BP readings";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &[
+        "PAT_MRN_ID",
+        "RECORDED_TIME",
+        "Systolic",
+        "Diastolic",
+        "HEART_RATE",
+        "BP_POSITION",
+        "pre or post procedure",
+    ];
+
+    /// A heart rate the monitor could not have measured, typically 0, is
+    /// treated as blank: the pressures in the same row are still good.
+    fn clean(&mut self) -> Vec<String> {
+        match self.hr.filter(|h| !(20..=250).contains(h)) {
+            Some(hr) => {
+                self.hr = None;
+                vec![format!("HEART_RATE: {hr} bpm is out of range, left blank")]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        require_mrn(&self.mrn)?;
+        if let Some(sys) = self.sys.filter(|s| !(40..=300).contains(s)) {
+            return Err(format!("Systolic: {sys} mmHg is out of range"));
+        }
+        if let Some(dia) = self.dia.filter(|d| !(10..=200).contains(d)) {
+            return Err(format!("Diastolic: {dia} mmHg is out of range"));
+        }
+        Ok(())
+    }
+}
+
+// --- Follow-up Types ---
+/// `PROCEDURE_DATE` as a date, or as a date and time the way
+/// `RECORDED_TIME` is written; the time of day is dropped.
+fn procedure_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(&raw, "%Y-%m-%d")
+        .or_else(|_| NaiveDateTime::parse_from_str(&raw, "%Y-%m-%d %H:%M:%S").map(|t| t.date()))
+        .map_err(|_| D::Error::custom(format!("'{raw}' is not a date")))
+}
+
+/// The day of a patient's procedure, from which follow-up is counted.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProcedureRecord {
+    #[serde(rename = "PAT_MRN_ID")]
+    pub mrn: String,
+    #[serde(rename = "PROCEDURE_DATE", deserialize_with = "procedure_date")]
+    pub date: NaiveDate,
+}
+
+impl CsvRecord for ProcedureRecord {
+    const DATASET: &'static str = "Procedure dates";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &["PAT_MRN_ID", "PROCEDURE_DATE"];
+
+    fn validate(&self) -> Result<(), String> {
+        require_mrn(&self.mrn)
+    }
+}
+
+// --- Medication Types ---
+/// One patient from `standup_results_meq.csv`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MeqRecord {
+    #[serde(rename = "PAT_MRN_ID")]
+    pub mrn: String,
+    #[serde(rename = "MEQ_Pre")]
+    pub pre: f32,
+    #[serde(rename = "MEQ_Post")]
+    pub post: f32,
+    /// As exported; optional, and `-inf` when the patient started from zero.
+    #[serde(
+        rename = "Percent_Reduction",
+        default,
+        deserialize_with = "optional_number"
+    )]
+    pub percent_reduction: Option<f32>,
+}
+
+impl MeqRecord {
+    /// Reduction from the pre dose in percent; negative for an increase.
+    /// Undefined for a patient who was on no medication before.
+    pub fn percent_reduction(&self) -> Option<f64> {
+        self.percent_reduction
+            .map(f64::from)
+            .or_else(|| {
+                (self.pre > 0.0).then(|| f64::from((self.pre - self.post) / self.pre) * 100.0)
+            })
+            .filter(|p| p.is_finite())
+    }
+}
+
+impl CsvRecord for MeqRecord {
+    const DATASET: &'static str = "MEQ doses";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &["PAT_MRN_ID", "MEQ_Pre", "MEQ_Post"];
+
+    fn validate(&self) -> Result<(), String> {
+        require_mrn(&self.mrn)?;
+        if self.pre < 0.0 || self.post < 0.0 {
+            return Err("MEQ doses cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The pressor drugs counted towards MEQ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Drug {
+    Midodrine,
+    Fludrocortisone,
+    Droxidopa,
+    Pyridostigmine,
+}
+
+impl Drug {
+    pub const ALL: [Drug; 4] = [
+        Drug::Midodrine,
+        Drug::Fludrocortisone,
+        Drug::Droxidopa,
+        Drug::Pyridostigmine,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Drug::Midodrine => "Midodrine",
+            Drug::Fludrocortisone => "Fludrocortisone",
+            Drug::Droxidopa => "Droxidopa",
+            Drug::Pyridostigmine => "Pyridostigmine",
+        }
+    }
+}
+
+impl TryFrom<String> for Drug {
+    type Error = String;
+
+    // Prescriptions are written by generic or brand name, in any case.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().as_str() {
+            "midodrine" | "proamatine" => Ok(Drug::Midodrine),
+            "fludrocortisone" | "florinef" => Ok(Drug::Fludrocortisone),
+            "droxidopa" | "northera" => Ok(Drug::Droxidopa),
+            "pyridostigmine" | "mestinon" => Ok(Drug::Pyridostigmine),
+            _ => Err(format!("unknown drug '{value}'")),
+        }
+    }
+}
+
+/// False for zero, negative, infinite and NaN amounts.
+fn is_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// One prescription line: a patient's regular dose of one drug, before or
+/// after the procedure.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PrescriptionRecord {
+    #[serde(rename = "PAT_MRN_ID")]
+    pub mrn: String,
+    #[serde(rename = "pre or post procedure")]
+    pub phase: Phase,
+    #[serde(rename = "Drug")]
+    pub drug: Drug,
+    /// Milligrams per dose.
+    #[serde(rename = "Dose_mg")]
+    pub dose: f64,
+    #[serde(rename = "Doses_per_day")]
+    pub per_day: f64,
+}
+
+impl PrescriptionRecord {
+    pub fn daily_mg(&self) -> f64 {
+        self.dose * self.per_day
+    }
+}
+
+impl CsvRecord for PrescriptionRecord {
+    const DATASET: &'static str = "Prescriptions";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &[
+        "PAT_MRN_ID",
+        "pre or post procedure",
+        "Drug",
+        "Dose_mg",
+        "Doses_per_day",
+    ];
+
+    fn validate(&self) -> Result<(), String> {
+        require_mrn(&self.mrn)?;
+        if !is_positive(self.dose) {
+            return Err(format!("Dose_mg: {} is not a positive dose", self.dose));
+        }
+        if !is_positive(self.per_day) {
+            return Err(format!(
+                "Doses_per_day: {} is not a positive frequency",
+                self.per_day
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One row of a midodrine equivalence table: how many ME one mg of the drug
+/// counts as.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EquivalenceRecord {
+    #[serde(rename = "Drug")]
+    pub drug: Drug,
+    #[serde(rename = "MEQ_per_mg")]
+    pub factor: f64,
+}
+
+impl CsvRecord for EquivalenceRecord {
+    const DATASET: &'static str = "MEQ equivalents";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &["Drug", "MEQ_per_mg"];
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_positive(self.factor) {
+            return Err(format!(
+                "MEQ_per_mg: {} is not a positive factor",
+                self.factor
+            ));
+        }
+        Ok(())
+    }
+}
+
+// --- CGI Types ---
+/// One patient from `standup_results_cgi.csv`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CgiRecord {
+    #[serde(rename = "PAT_MRN_ID")]
+    pub mrn: String,
+    #[serde(rename = "CGI-I Score")]
+    pub score: i32,
+}
+
+impl CsvRecord for CgiRecord {
+    const DATASET: &'static str = "CGI-I scores";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &["PAT_MRN_ID", "CGI-I Score"];
+
+    fn validate(&self) -> Result<(), String> {
+        require_mrn(&self.mrn)?;
+        if !(1..=7).contains(&self.score) {
+            return Err(format!(
+                "CGI-I Score: {} is not on the 1-7 scale",
+                self.score
+            ));
+        }
+        Ok(())
+    }
+}
+
+// --- Summary Types ---
+/// One patient from `standup_results_bp.csv`, the spreadsheet's own summary
+/// of the orthostatic systolic drop. It is checked against the readings,
+/// not shown; see `data::consistency`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SummaryRecord {
+    #[serde(rename = "Patient ID")]
+    pub mrn: String,
+    #[serde(rename = "Patient", default, deserialize_with = "optional_number")]
+    pub number: Option<usize>,
+    #[serde(
+        rename = "Mean Pre Orthostatic Systolic BP",
+        deserialize_with = "optional_number"
+    )]
+    pub pre: Option<f64>,
+    #[serde(
+        rename = "Mean Post Orthostatic Systolic BP",
+        deserialize_with = "optional_number"
+    )]
+    pub post: Option<f64>,
+    #[serde(
+        rename = "Pre–Post Δ Orthostatic Systolic BP",
+        default,
+        deserialize_with = "optional_number"
+    )]
+    pub change: Option<f64>,
+}
+
+impl CsvRecord for SummaryRecord {
+    const DATASET: &'static str = "BP summary";
+    const REQUIRED_COLUMNS: &'static [&'static str] = &[
+        "Patient ID",
+        "Mean Pre Orthostatic Systolic BP",
+        "Mean Post Orthostatic Systolic BP",
+    ];
+    // Cohort means and test results follow the patients, without a number.
+    const TABLE_END_COLUMN: Option<&'static str> = Some("Patient");
+
+    fn validate(&self) -> Result<(), String> {
+        if self.mrn.is_empty() {
+            return Err("Patient ID: missing".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ingest::load_csv;
+
+    const HEADER: &str =
+        "PAT_MRN_ID,RECORDED_TIME,Systolic,Diastolic,HEART_RATE,BP_POSITION,pre or post procedure";
+
+    #[test]
+    fn implausible_heart_rate_is_cleared_not_rejected() {
+        let csv = format!("{HEADER}\nSU001,2025-09-25 14:29:00,116,85,0,Standing,post\n");
+        let report = load_csv::<BpRecord>(&csv).unwrap();
+        assert!(report.rejected.is_empty());
+        assert_eq!(report.records.len(), 1);
+        let r = &report.records[0];
+        assert_eq!((r.sys, r.dia, r.hr), (Some(116), Some(85), None));
+        assert_eq!(report.cleared.len(), 1);
+        assert_eq!(report.cleared[0].line, 2);
+        assert_eq!(report.cleared[0].mrn.as_deref(), Some("SU001"));
+    }
+
+    #[test]
+    fn implausible_pressure_rejects_the_row() {
+        let csv = format!("{HEADER}\nSU001,2025-09-25 14:29:00,400,85,70,Standing,post\n");
+        let report = load_csv::<BpRecord>(&csv).unwrap();
+        assert!(report.records.is_empty());
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].mrn.as_deref(), Some("SU001"));
+    }
+}
@@ -0,0 +1,688 @@
+//! Paired-sample statistics for the Results slide.
+//!
+//! Everything here is implemented directly so the numbers on screen do not
+//! depend on a stats crate's defaults. Small-sample tests are exact where the
+//! cohort size allows; the bootstrap uses a fixed seed so the deck shows the
+//! same interval on every run.
+
+/// Two-sided significance level behind every "significant" label.
+pub const ALPHA: f64 = 0.05;
+
+/// Resamples drawn for bootstrap intervals.
+const BOOTSTRAP_RESAMPLES: usize = 4000;
+
+/// Largest number of non-zero differences for which the Wilcoxon null
+/// distribution is enumerated exactly; above it the normal approximation is
+/// used.
+const WILCOXON_EXACT_MAX: usize = 50;
+
+// --- Special functions ---
+
+/// Natural log of the gamma function (Lanczos approximation, g = 7).
+fn ln_gamma(x: f64) -> f64 {
+    const COEF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula.
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEF[1..]
+        .iter()
+        .enumerate()
+        .fold(COEF[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz).
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let guard = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..300 {
+        let m = f64::from(m);
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        let mut delta = 1.0;
+        for coef in [even, odd] {
+            d = 1.0 / guard(1.0 + coef * d);
+            c = guard(1.0 + coef / c);
+            delta = d * c;
+            h *= delta;
+        }
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+pub fn inc_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_cf(a, b, x) / a
+    } else {
+        1.0 - ln_front.exp() * beta_cf(b, a, 1.0 - x) / b
+    }
+}
+
+/// Standard normal cumulative distribution function.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function (Chebyshev fit, relative error < 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Two-sided p-value of Student's t with `df` degrees of freedom.
+pub fn t_two_sided_p(t: f64, df: f64) -> f64 {
+    inc_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// The t value with `p` two-sided tail probability, e.g. 0.05 for a 95% CI.
+pub fn t_critical(p: f64, df: f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1000.0);
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if t_two_sided_p(mid, df) > p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+// --- Descriptives ---
+
+pub fn mean(xs: &[f64]) -> Option<f64> {
+    (!xs.is_empty()).then(|| xs.iter().sum::<f64>() / xs.len() as f64)
+}
+
+pub fn median(xs: &[f64]) -> Option<f64> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+/// The `q` quantile by linear interpolation between order statistics
+/// (Hyndman and Fan type 7, the default in R and NumPy).
+pub fn quantile(xs: &[f64], q: f64) -> Option<f64> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let last = sorted.len().checked_sub(1)?;
+    let h = q.clamp(0.0, 1.0) * last as f64;
+    let (lo, hi) = (h.floor() as usize, h.ceil() as usize);
+    Some(sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo]))
+}
+
+/// Sample standard deviation (n - 1 denominator).
+pub fn sd(xs: &[f64]) -> Option<f64> {
+    let m = mean(xs)?;
+    (xs.len() > 1).then(|| {
+        let ss: f64 = xs.iter().map(|x| (x - m).powi(2)).sum();
+        (ss / (xs.len() - 1) as f64).sqrt()
+    })
+}
+
+/// Average ranks (1-based) of `xs`, ties sharing the mean of their ranks.
+pub fn ranks(xs: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..xs.len()).collect();
+    order.sort_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+    let mut ranks = vec![0.0; xs.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && xs[order[j + 1]] == xs[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+// --- Tests ---
+
+/// Result of a hypothesis test: its statistic and two-sided p-value.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p: f64,
+}
+
+impl TestResult {
+    pub fn significant(&self) -> bool {
+        self.p < ALPHA
+    }
+}
+
+/// Paired t-test on within-patient differences.
+pub fn paired_t(diffs: &[f64]) -> Option<TestResult> {
+    let n = diffs.len();
+    let (m, s) = (mean(diffs)?, sd(diffs)?);
+    let df = (n - 1) as f64;
+    if s == 0.0 {
+        let p = if m == 0.0 { 1.0 } else { 0.0 };
+        return Some(TestResult {
+            statistic: f64::INFINITY.copysign(m),
+            p,
+        });
+    }
+    let t = m / (s / (n as f64).sqrt());
+    Some(TestResult {
+        statistic: t,
+        p: t_two_sided_p(t, df),
+    })
+}
+
+/// Wilcoxon signed-rank test. Zero differences are dropped and tied
+/// magnitudes get average ranks; the statistic is W+, the rank sum of the
+/// positive differences. Exact for up to [`WILCOXON_EXACT_MAX`] non-zero
+/// differences (conditional on the ties), normal approximation above.
+pub fn wilcoxon(diffs: &[f64]) -> Option<TestResult> {
+    let nonzero: Vec<f64> = diffs.iter().copied().filter(|d| *d != 0.0).collect();
+    let n = nonzero.len();
+    if n == 0 {
+        return None;
+    }
+    let magnitudes: Vec<f64> = nonzero.iter().map(|d| d.abs()).collect();
+    let ranks = ranks(&magnitudes);
+    let w_plus: f64 = nonzero
+        .iter()
+        .zip(&ranks)
+        .filter(|(d, _)| **d > 0.0)
+        .map(|(_, r)| r)
+        .sum();
+
+    let p = if n <= WILCOXON_EXACT_MAX {
+        // Ranks are multiples of one half, so doubling them makes the null
+        // distribution of W+ a subset-sum count over integers.
+        let doubled: Vec<usize> = ranks.iter().map(|r| (r * 2.0).round() as usize).collect();
+        let total: usize = doubled.iter().sum();
+        let mut counts = vec![0.0_f64; total + 1];
+        counts[0] = 1.0;
+        for &r in &doubled {
+            for s in (r..=total).rev() {
+                counts[s] += counts[s - r];
+            }
+        }
+        let all: f64 = counts.iter().sum();
+        let w = (w_plus * 2.0).round() as usize;
+        let lower: f64 = counts[..=w].iter().sum::<f64>() / all;
+        let upper: f64 = counts[w..].iter().sum::<f64>() / all;
+        (2.0 * lower.min(upper)).min(1.0)
+    } else {
+        let nf = n as f64;
+        let expected = nf * (nf + 1.0) / 4.0;
+        let tie_term: f64 = tie_sizes(&magnitudes).map(|t| t * t * t - t).sum::<f64>() / 48.0;
+        let variance = nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0 - tie_term;
+        let z = ((w_plus - expected).abs() - 0.5).max(0.0) / variance.sqrt();
+        (2.0 * (1.0 - normal_cdf(z))).min(1.0)
+    };
+    Some(TestResult {
+        statistic: w_plus,
+        p,
+    })
+}
+
+/// Sizes of the groups of equal values in `xs`.
+fn tie_sizes(xs: &[f64]) -> impl Iterator<Item = f64> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mut sizes = Vec::new();
+    let mut i = 0;
+    while i < sorted.len() {
+        let j = sorted[i..].iter().take_while(|x| **x == sorted[i]).count();
+        sizes.push(j as f64);
+        i += j;
+    }
+    sizes.into_iter()
+}
+
+/// P(X <= k) for X ~ Binomial(n, p).
+pub fn binomial_cdf(k: u64, n: u64, p: f64) -> f64 {
+    if k >= n {
+        return 1.0;
+    }
+    // I_{1-p}(n - k, k + 1)
+    inc_beta((n - k) as f64, (k + 1) as f64, 1.0 - p)
+}
+
+/// Exact sign test; the statistic is the number of positive differences.
+pub fn sign_test(diffs: &[f64]) -> Option<TestResult> {
+    let positive = diffs.iter().filter(|d| **d > 0.0).count() as u64;
+    let negative = diffs.iter().filter(|d| **d < 0.0).count() as u64;
+    let n = positive + negative;
+    if n == 0 {
+        return None;
+    }
+    let p = (2.0 * binomial_cdf(positive.min(negative), n, 0.5)).min(1.0);
+    Some(TestResult {
+        statistic: positive as f64,
+        p,
+    })
+}
+
+// --- Intervals and effect sizes ---
+
+/// A two-sided 95% confidence interval.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub fn excludes_zero(&self) -> bool {
+        self.lower > 0.0 || self.upper < 0.0
+    }
+}
+
+/// The `p` quantile of Beta(a, b), by bisection on [`inc_beta`].
+fn beta_quantile(p: f64, a: f64, b: f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if inc_beta(a, b, mid) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Exact (Clopper-Pearson) 95% CI for a proportion of `k` in `n`.
+pub fn clopper_pearson(k: usize, n: usize) -> Option<Interval> {
+    if n == 0 || k > n {
+        return None;
+    }
+    let (k, n) = (k as f64, n as f64);
+    Some(Interval {
+        lower: if k == 0.0 {
+            0.0
+        } else {
+            beta_quantile(ALPHA / 2.0, k, n - k + 1.0)
+        },
+        upper: if k == n {
+            1.0
+        } else {
+            beta_quantile(1.0 - ALPHA / 2.0, k + 1.0, n - k)
+        },
+    })
+}
+
+/// t-based 95% CI for the mean of `xs`.
+pub fn t_interval(xs: &[f64]) -> Option<Interval> {
+    let (m, s) = (mean(xs)?, sd(xs)?);
+    let half = t_critical(ALPHA, (xs.len() - 1) as f64) * s / (xs.len() as f64).sqrt();
+    Some(Interval {
+        lower: m - half,
+        upper: m + half,
+    })
+}
+
+/// SplitMix64: small, seedable, and good enough for resampling indices.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Percentile bootstrap 95% CI for `statistic` over resamples of `xs`.
+pub fn bootstrap_interval(xs: &[f64], statistic: impl Fn(&[f64]) -> f64) -> Option<Interval> {
+    if xs.len() < 2 {
+        return None;
+    }
+    let mut rng = SplitMix64(0x5743_414E_4455_5031);
+    let mut sample = vec![0.0; xs.len()];
+    let mut estimates: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            for slot in sample.iter_mut() {
+                *slot = xs[rng.below(xs.len())];
+            }
+            statistic(&sample)
+        })
+        .collect();
+    estimates.sort_by(f64::total_cmp);
+    let at = |q: f64| estimates[((q * (estimates.len() - 1) as f64).round()) as usize];
+    Some(Interval {
+        lower: at(ALPHA / 2.0),
+        upper: at(1.0 - ALPHA / 2.0),
+    })
+}
+
+/// Cohen's d_z: mean difference over the SD of the differences.
+pub fn cohens_dz(diffs: &[f64]) -> Option<f64> {
+    let s = sd(diffs)?;
+    (s > 0.0).then(|| mean(diffs).unwrap_or_default() / s)
+}
+
+/// Matched-pairs rank-biserial correlation, from -1 (all decreases) to 1.
+pub fn rank_biserial(diffs: &[f64]) -> Option<f64> {
+    let nonzero: Vec<f64> = diffs.iter().copied().filter(|d| *d != 0.0).collect();
+    if nonzero.is_empty() {
+        return None;
+    }
+    let ranks = ranks(&nonzero.iter().map(|d| d.abs()).collect::<Vec<_>>());
+    let total: f64 = ranks.iter().sum();
+    let positive: f64 = nonzero
+        .iter()
+        .zip(&ranks)
+        .filter(|(d, _)| **d > 0.0)
+        .map(|(_, r)| r)
+        .sum();
+    Some((2.0 * positive - total) / total)
+}
+
+// --- Paired comparison ---
+
+/// Everything the Results slide reports about a pre/post comparison. The
+/// averages are `None` without pairs, rather than zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PairedSummary {
+    pub n: usize,
+    pub pre: Option<f64>,
+    pub post: Option<f64>,
+    /// Mean of post minus pre.
+    pub change: Option<f64>,
+    /// Sample standard deviation of the within-patient change; needs two pairs.
+    pub change_sd: Option<f64>,
+    pub t_test: Option<TestResult>,
+    pub wilcoxon: Option<TestResult>,
+    pub sign: Option<TestResult>,
+    /// t-based CI of the mean change.
+    pub ci: Option<Interval>,
+    /// Bootstrap CI of the mean change.
+    pub ci_boot: Option<Interval>,
+    pub cohens_dz: Option<f64>,
+    pub rank_biserial: Option<f64>,
+}
+
+impl PairedSummary {
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let (pre, post): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+        if pre.is_empty() {
+            return Self::default();
+        }
+        let diffs: Vec<f64> = pre.iter().zip(&post).map(|(a, b)| b - a).collect();
+        Self {
+            n: diffs.len(),
+            pre: mean(&pre),
+            post: mean(&post),
+            change: mean(&diffs),
+            change_sd: sd(&diffs),
+            t_test: paired_t(&diffs),
+            wilcoxon: wilcoxon(&diffs),
+            sign: sign_test(&diffs),
+            ci: t_interval(&diffs),
+            ci_boot: bootstrap_interval(&diffs, |xs| mean(xs).unwrap_or_default()),
+            cohens_dz: cohens_dz(&diffs),
+            rank_biserial: rank_biserial(&diffs),
+        }
+    }
+
+    /// Significance as the slides state it: the Wilcoxon signed-rank test,
+    /// since the differences are not assumed to be normal.
+    pub fn significant(&self) -> bool {
+        self.wilcoxon.is_some_and(|w| w.significant())
+    }
+}
+
+// --- Correlation ---
+
+/// Pearson's r; `None` with fewer than two pairs or no spread on either side.
+pub fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let (mx, my) = (mean(xs)?, mean(ys)?);
+    if xs.len() != ys.len() || xs.len() < 2 {
+        return None;
+    }
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        sxy += (x - mx) * (y - my);
+        sxx += (x - mx).powi(2);
+        syy += (y - my).powi(2);
+    }
+    (sxx > 0.0 && syy > 0.0).then(|| sxy / (sxx * syy).sqrt())
+}
+
+/// Spearman's rho, as Pearson's r of the ranks, with a two-sided p-value
+/// from the t approximation on n - 2 degrees of freedom. Needs three pairs.
+pub fn spearman(xs: &[f64], ys: &[f64]) -> Option<TestResult> {
+    if xs.len() < 3 {
+        return None;
+    }
+    let rho = pearson(&ranks(xs), &ranks(ys))?;
+    let df = (xs.len() - 2) as f64;
+    let p = if rho.abs() >= 1.0 {
+        0.0
+    } else {
+        t_two_sided_p(rho * (df / (1.0 - rho * rho)).sqrt(), df)
+    };
+    Some(TestResult { statistic: rho, p })
+}
+
+/// A least-squares straight line, y = intercept + slope · x.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LinearFit {
+    pub intercept: f64,
+    pub slope: f64,
+}
+
+impl LinearFit {
+    /// `None` with fewer than two pairs or all x equal.
+    pub fn fit(xs: &[f64], ys: &[f64]) -> Option<Self> {
+        let (mx, my) = (mean(xs)?, mean(ys)?);
+        if xs.len() != ys.len() || xs.len() < 2 {
+            return None;
+        }
+        let sxy: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mx) * (y - my)).sum();
+        let sxx: f64 = xs.iter().map(|x| (x - mx).powi(2)).sum();
+        (sxx > 0.0).then(|| {
+            let slope = sxy / sxx;
+            Self {
+                intercept: my - slope * mx,
+                slope,
+            }
+        })
+    }
+
+    pub fn at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+/// "p = 0.012", or "p < 0.001" below that.
+pub fn format_p(p: f64) -> String {
+    if p < 0.001 {
+        "p < 0.001".to_string()
+    } else {
+        format!("p = {p:.3}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference values are R's (`pt`, `qt`, `t.test`, `wilcox.test`,
+    /// `binom.test`, `quantile`, `cor.test`, `lm`), to the digits it prints.
+    fn close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn t_distribution() {
+        close(t_two_sided_p(2.0, 10.0), 0.073_388_03, 1e-7);
+        close(t_two_sided_p(0.0, 5.0), 1.0, 1e-12);
+        close(t_critical(0.05, 10.0), 2.228_139, 1e-6);
+        close(t_critical(0.05, 1.0), 12.706_20, 1e-4);
+        close(normal_cdf(1.96), 0.975_002_1, 1e-6);
+    }
+
+    #[test]
+    fn paired_t_matches_r() {
+        let t = paired_t(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        close(t.statistic, 4.242_641, 1e-6);
+        close(t.p, 0.013_235_60, 1e-7);
+        let interval = t_interval(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        close(interval.lower, 1.036_757, 1e-6);
+        close(interval.upper, 4.963_243, 1e-6);
+        assert!(paired_t(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn wilcoxon_is_exact_for_small_samples() {
+        let w = wilcoxon(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_eq!(w.statistic, 15.0);
+        close(w.p, 0.0625, 1e-12);
+        let w = wilcoxon(&[1.0, -2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        assert_eq!(w.statistic, 19.0);
+        close(w.p, 0.093_75, 1e-12);
+        // Zeros are dropped.
+        assert_eq!(
+            wilcoxon(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]),
+            wilcoxon(&[1.0, 2.0, 3.0, 4.0, 5.0])
+        );
+        assert!(wilcoxon(&[0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn sign_test_and_binomial() {
+        close(binomial_cdf(2, 10, 0.5), 56.0 / 1024.0, 1e-12);
+        let s = sign_test(&[1.0, 2.0, -1.0, 3.0, 0.0]).unwrap();
+        assert_eq!(s.statistic, 3.0);
+        close(s.p, 0.625, 1e-12);
+    }
+
+    #[test]
+    fn clopper_pearson_matches_binom_test() {
+        let ci = clopper_pearson(5, 10).unwrap();
+        close(ci.lower, 0.187_086_0, 1e-6);
+        close(ci.upper, 0.812_914_0, 1e-6);
+        let ci = clopper_pearson(0, 10).unwrap();
+        assert_eq!(ci.lower, 0.0);
+        close(ci.upper, 0.308_497_1, 1e-6);
+        let ci = clopper_pearson(10, 10).unwrap();
+        close(ci.lower, 0.691_502_9, 1e-6);
+        assert_eq!(ci.upper, 1.0);
+        assert!(clopper_pearson(0, 0).is_none());
+        assert!(clopper_pearson(3, 2).is_none());
+    }
+
+    #[test]
+    fn quantiles_are_type_7() {
+        let xs = [4.0, 1.0, 3.0, 2.0];
+        assert_eq!(quantile(&xs, 0.25), Some(1.75));
+        assert_eq!(quantile(&xs, 0.75), Some(3.25));
+        assert_eq!(quantile(&xs, 0.0), Some(1.0));
+        assert_eq!(quantile(&xs, 1.0), Some(4.0));
+        assert_eq!(median(&xs), Some(2.5));
+        assert_eq!(quantile(&[], 0.5), None);
+    }
+
+    #[test]
+    fn ties_share_their_mean_rank() {
+        assert_eq!(ranks(&[10.0, 20.0, 10.0, 30.0]), [1.5, 3.0, 1.5, 4.0]);
+    }
+
+    #[test]
+    fn spearman_ranks_ties_and_uses_the_t_approximation() {
+        let rho = spearman(
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            &[2.0, 1.0, 4.0, 4.0, 3.0, 6.0],
+        )
+        .unwrap();
+        close(rho.statistic, 0.753_702_3, 1e-6);
+        close(rho.p, 0.083_52, 1e-5);
+        // Monotone but far from linear.
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        let up = spearman(&xs, &[1.0, 10.0, 100.0, 1000.0]).unwrap();
+        assert_eq!((up.statistic, up.p), (1.0, 0.0));
+        let down = spearman(&xs, &[8.0, 4.0, 2.0, 1.0]).unwrap();
+        assert_eq!((down.statistic, down.p), (-1.0, 0.0));
+        assert!(spearman(&[1.0, 2.0], &[2.0, 1.0]).is_none());
+        assert!(spearman(&xs, &[5.0; 4]).is_none());
+    }
+
+    #[test]
+    fn pearson_and_linear_fit() {
+        let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let ys = [2.0, 4.0, 5.0, 4.0, 5.0];
+        close(pearson(&xs, &ys).unwrap(), 0.774_596_7, 1e-6);
+        close(
+            pearson(&xs, &[9.0, 7.0, 5.0, 3.0, 1.0]).unwrap(),
+            -1.0,
+            1e-12,
+        );
+        assert!(pearson(&[1.0], &[1.0]).is_none());
+        assert!(pearson(&xs, &[3.0; 5]).is_none());
+        assert!(pearson(&xs, &ys[..4]).is_none());
+
+        let fit = LinearFit::fit(&xs, &ys).unwrap();
+        close(fit.slope, 0.6, 1e-12);
+        close(fit.intercept, 2.2, 1e-12);
+        close(fit.at(10.0), 8.2, 1e-12);
+        assert!(LinearFit::fit(&[2.0; 3], &[1.0, 2.0, 3.0]).is_none());
+        assert!(LinearFit::fit(&[1.0], &[1.0]).is_none());
+    }
+}
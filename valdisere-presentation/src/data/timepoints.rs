@@ -0,0 +1,258 @@
+//! Follow-up timepoints: where a reading falls relative to the patient's
+//! procedure.
+//!
+//! The protocol schedules follow-up at 2 weeks, 3 and 6 months, 1 and 2
+//! years. Readings after the procedure are placed in the window of the
+//! nearest scheduled visit, counted in days from the procedure date; the
+//! windows meet halfway between neighbouring visits. The first days after
+//! the procedure are a window of their own, since the patient is still on
+//! the ward and nowhere near the 2-week visit. Readings before it are the
+//! baseline. The `pre or post procedure` column still decides which side
+//! of the procedure a reading is on, so a date that contradicts it cannot
+//! move a reading across.
+//!
+//! Procedure dates come from the optional `Procedures` export. A patient
+//! without one gets an estimate: the day of their last pre-procedure reading
+//! that precedes the first post-procedure one, or, with no such reading, the
+//! day of the first post-procedure reading.
+
+use crate::data::hemodynamics::orthostatic_drops;
+use crate::data::records::{BpRecord, Phase, ProcedureRecord};
+use crate::data::visits::{Aggregation, Worst};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Readings up to this many days after the procedure are peri-procedural.
+pub const PERIPROCEDURAL_DAYS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Timepoint {
+    Baseline,
+    /// Days 0 to [`PERIPROCEDURAL_DAYS`].
+    Periprocedural,
+    TwoWeeks,
+    ThreeMonths,
+    SixMonths,
+    OneYear,
+    TwoYears,
+}
+
+impl Timepoint {
+    pub const ALL: [Timepoint; 7] = [
+        Timepoint::Baseline,
+        Timepoint::Periprocedural,
+        Timepoint::TwoWeeks,
+        Timepoint::ThreeMonths,
+        Timepoint::SixMonths,
+        Timepoint::OneYear,
+        Timepoint::TwoYears,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Timepoint::Baseline => "Baseline",
+            Timepoint::Periprocedural => "0–3 d",
+            Timepoint::TwoWeeks => "2 wk",
+            Timepoint::ThreeMonths => "3 mo",
+            Timepoint::SixMonths => "6 mo",
+            Timepoint::OneYear => "1 yr",
+            Timepoint::TwoYears => "2 yr",
+        }
+    }
+
+    /// Days after the procedure of the scheduled visit; `None` for the
+    /// windows that are not one.
+    pub fn target_days(&self) -> Option<i64> {
+        match self {
+            Timepoint::Baseline | Timepoint::Periprocedural => None,
+            Timepoint::TwoWeeks => Some(14),
+            Timepoint::ThreeMonths => Some(91),
+            Timepoint::SixMonths => Some(182),
+            Timepoint::OneYear => Some(365),
+            Timepoint::TwoYears => Some(730),
+        }
+    }
+
+    /// The follow-up window holding a reading `days` after the procedure.
+    /// Anything up to [`PERIPROCEDURAL_DAYS`] is peri-procedural, and
+    /// anything past the last visit counts towards it.
+    pub fn after(days: i64) -> Self {
+        if days <= PERIPROCEDURAL_DAYS {
+            return Timepoint::Periprocedural;
+        }
+        Self::ALL
+            .iter()
+            .copied()
+            .filter_map(|t| Some((t, t.target_days()?)))
+            .min_by_key(|(_, target)| (target - days).abs())
+            .map_or(Timepoint::TwoWeeks, |(t, _)| t)
+    }
+
+    pub fn phase(&self) -> Phase {
+        match self {
+            Timepoint::Baseline => Phase::Pre,
+            _ => Phase::Post,
+        }
+    }
+}
+
+/// Each patient's procedure date, and whether it was estimated.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProcedureDates {
+    dates: BTreeMap<String, (NaiveDate, bool)>,
+}
+
+impl ProcedureDates {
+    /// Dates from `procedures`, and estimates for every other patient with
+    /// a post-procedure reading.
+    pub fn build(readings: &[BpRecord], procedures: &[ProcedureRecord]) -> Self {
+        let mut first_post: BTreeMap<&str, NaiveDateTime> = BTreeMap::new();
+        for r in readings.iter().filter(|r| r.phase == Phase::Post) {
+            let first = first_post.entry(r.mrn.as_str()).or_insert(r.time);
+            *first = (*first).min(r.time);
+        }
+        let mut last_pre: BTreeMap<&str, NaiveDateTime> = BTreeMap::new();
+        for r in readings.iter().filter(|r| r.phase == Phase::Pre) {
+            if first_post
+                .get(r.mrn.as_str())
+                .is_some_and(|&post| r.time <= post)
+            {
+                let last = last_pre.entry(r.mrn.as_str()).or_insert(r.time);
+                *last = (*last).max(r.time);
+            }
+        }
+
+        let mut dates: BTreeMap<String, (NaiveDate, bool)> = first_post
+            .iter()
+            .map(|(&mrn, post)| {
+                let estimate = last_pre.get(mrn).unwrap_or(post).date();
+                (mrn.to_string(), (estimate, true))
+            })
+            .collect();
+        for p in procedures {
+            dates.insert(p.mrn.clone(), (p.date, false));
+        }
+        Self { dates }
+    }
+
+    pub fn get(&self, mrn: &str) -> Option<NaiveDate> {
+        self.dates.get(mrn).map(|&(date, _)| date)
+    }
+
+    /// Patients whose date is an estimate.
+    pub fn estimated(&self) -> usize {
+        self.dates
+            .values()
+            .filter(|(_, estimated)| *estimated)
+            .count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+
+    /// The timepoint of a reading by `mrn` at `time` in `phase`. `None` for
+    /// a post-procedure reading of a patient without a procedure date,
+    /// which only happens when `dates` was built from other readings.
+    pub fn timepoint(&self, mrn: &str, phase: Phase, time: NaiveDateTime) -> Option<Timepoint> {
+        match phase {
+            Phase::Pre => Some(Timepoint::Baseline),
+            Phase::Post => {
+                let days = (time.date() - self.get(mrn)?).num_days();
+                Some(Timepoint::after(days))
+            }
+        }
+    }
+}
+
+/// The cohort at one timepoint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimepointSummary {
+    pub timepoint: Timepoint,
+    /// Patients with at least one reading.
+    pub assessed: usize,
+    /// Patients with at least one stand test.
+    pub stand_tested: usize,
+    /// Mean over patients of each one's orthostatic systolic drop under the
+    /// rule; `None` without a stand test.
+    pub sys_drop: Option<f64>,
+    pub dia_drop: Option<f64>,
+}
+
+/// Patients assessed and the mean orthostatic drop at every timepoint, in
+/// [`Timepoint::ALL`] order. Each patient's visits within a window are
+/// reduced to one value per measure under `rule`, as for pre and post.
+pub fn follow_up(
+    readings: &[BpRecord],
+    dates: &ProcedureDates,
+    rule: Aggregation,
+) -> Vec<TimepointSummary> {
+    let mut assessed: BTreeMap<Timepoint, BTreeSet<&str>> = BTreeMap::new();
+    for r in readings {
+        if let Some(t) = dates.timepoint(&r.mrn, r.phase, r.time) {
+            assessed.entry(t).or_default().insert(r.mrn.as_str());
+        }
+    }
+
+    // Per timepoint and patient, each visit's systolic and diastolic drops.
+    type Visits = BTreeMap<usize, [Vec<f64>; 2]>;
+    let mut drops: BTreeMap<Timepoint, BTreeMap<String, Visits>> = BTreeMap::new();
+    for d in orthostatic_drops(readings) {
+        let Some(t) = dates.timepoint(&d.mrn, d.phase, d.time) else {
+            continue;
+        };
+        let [sys, dia] = drops
+            .entry(t)
+            .or_default()
+            .entry(d.mrn)
+            .or_default()
+            .entry(d.visit)
+            .or_default();
+        sys.push(f64::from(d.sys));
+        dia.extend(d.dia.map(f64::from));
+    }
+
+    Timepoint::ALL
+        .into_iter()
+        .map(|timepoint| {
+            let patients = drops.remove(&timepoint).unwrap_or_default();
+            let mean_of = |measure: usize| {
+                let values: Vec<f64> = patients
+                    .values()
+                    .filter_map(|visits| {
+                        let visits: Vec<Vec<f64>> =
+                            visits.values().map(|v| v[measure].clone()).collect();
+                        rule.apply(&visits, Worst::Highest)
+                    })
+                    .collect();
+                (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+            };
+            TimepointSummary {
+                timepoint,
+                assessed: assessed.get(&timepoint).map_or(0, BTreeSet::len),
+                stand_tested: patients.len(),
+                sys_drop: mean_of(0),
+                dia_drop: mean_of(1),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_days_are_their_own_window() {
+        assert_eq!(Timepoint::after(0), Timepoint::Periprocedural);
+        assert_eq!(Timepoint::after(3), Timepoint::Periprocedural);
+        assert_eq!(Timepoint::after(4), Timepoint::TwoWeeks);
+        assert_eq!(Timepoint::after(52), Timepoint::TwoWeeks);
+        assert_eq!(Timepoint::after(53), Timepoint::ThreeMonths);
+        assert_eq!(Timepoint::after(2000), Timepoint::TwoYears);
+    }
+}
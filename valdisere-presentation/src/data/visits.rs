@@ -0,0 +1,272 @@
+//! Readings grouped into visits, and the rule that turns a patient's visits
+//! into one number per phase.
+//!
+//! Most patients were measured on several days before and after the
+//! procedure, some of them dozens of times. Pooling every reading lets the
+//! heavily measured patients dominate, so each patient first gets one value
+//! per phase, chosen by an [`Aggregation`] over their visits. A visit is a
+//! run of readings with no gap longer than [`VISIT_GAP_HOURS`]; in practice,
+//! one day on the ward. Within a visit, a stand test is a run with no gap
+//! longer than [`STAND_TEST_GAP_MINUTES`]. The drops, the OH
+//! classification and the position profiles all use these two definitions,
+//! so their counts agree.
+
+use crate::data::records::{BpRecord, Phase, Position};
+use chrono::{NaiveDateTime, TimeDelta};
+use std::collections::BTreeMap;
+
+/// Readings further apart than this belong to different visits. Stand tests
+/// on the same day are at most a few hours apart; visits are days apart.
+pub const VISIT_GAP_HOURS: i64 = 8;
+
+/// Readings of one visit further apart than this belong to different stand
+/// tests. A lying-sitting-standing sequence in the export takes 3-15
+/// minutes.
+pub const STAND_TEST_GAP_MINUTES: i64 = 20;
+
+/// Each patient's readings per phase, keyed by MRN and whether they come
+/// after the procedure, in `RECORDED_TIME` order. Every grouping of
+/// readings into visits and stand tests starts from this.
+pub fn by_patient_phase<'a>(
+    records: impl IntoIterator<Item = &'a BpRecord>,
+) -> BTreeMap<(&'a str, bool), Vec<&'a BpRecord>> {
+    let mut by_patient: BTreeMap<(&str, bool), Vec<&BpRecord>> = BTreeMap::new();
+    for r in records {
+        by_patient
+            .entry((r.mrn.as_str(), r.phase == Phase::Post))
+            .or_default()
+            .push(r);
+    }
+    for readings in by_patient.values_mut() {
+        readings.sort_by_key(|r| r.time);
+    }
+    by_patient
+}
+
+/// Splits readings in time order wherever two are more than `gap` apart.
+fn runs<'a, 'r>(readings: &'r [&'a BpRecord], gap: TimeDelta) -> Vec<&'r [&'a BpRecord]> {
+    readings.chunk_by(|a, b| b.time - a.time <= gap).collect()
+}
+
+/// One patient's readings on one occasion, within one phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Visit<'a> {
+    pub mrn: &'a str,
+    pub phase: Phase,
+    /// Which of the patient's visits in this phase, counting from 0.
+    pub index: usize,
+    pub start: NaiveDateTime,
+    /// In `RECORDED_TIME` order.
+    pub readings: Vec<&'a BpRecord>,
+}
+
+impl<'a> Visit<'a> {
+    /// The visit's stand tests: runs of readings no more than
+    /// [`STAND_TEST_GAP_MINUTES`] apart.
+    pub fn stand_tests(&self) -> Vec<&[&'a BpRecord]> {
+        runs(&self.readings, TimeDelta::minutes(STAND_TEST_GAP_MINUTES))
+    }
+}
+
+/// Splits each patient's readings, per phase, into visits; ordered by
+/// patient, then phase, then time.
+pub fn visits(records: &[BpRecord]) -> Vec<Visit<'_>> {
+    let mut visits = Vec::new();
+    for ((mrn, post), readings) in by_patient_phase(records) {
+        for (index, run) in runs(&readings, TimeDelta::hours(VISIT_GAP_HOURS))
+            .into_iter()
+            .enumerate()
+        {
+            visits.push(Visit {
+                mrn,
+                phase: if post { Phase::Post } else { Phase::Pre },
+                index,
+                start: run[0].time,
+                readings: run.to_vec(),
+            });
+        }
+    }
+    visits
+}
+
+/// Which end of the scale is worse for a measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Worst {
+    /// Pressures: the nadir.
+    Lowest,
+    /// Heart rate and orthostatic drops.
+    Highest,
+}
+
+/// How a patient's visits become one value for a phase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aggregation {
+    /// The mean of each visit's mean, so every visit counts once however
+    /// many readings it has.
+    #[default]
+    Mean,
+    /// The mean of the first visit.
+    First,
+    /// The single worst reading of any visit, e.g. the standing nadir.
+    Worst,
+    /// The mean of the last visit.
+    Last,
+    /// The mean of every reading, whatever visit it is from. This is what
+    /// `standup_data.json` has always held, so the exporter keeps it.
+    Pooled,
+}
+
+impl Aggregation {
+    pub const ALL: [Aggregation; 5] = [
+        Aggregation::Mean,
+        Aggregation::First,
+        Aggregation::Worst,
+        Aggregation::Last,
+        Aggregation::Pooled,
+    ];
+
+    /// The rules the results slide offers. [`Aggregation::Pooled`] is left
+    /// out: it exists so the exporter can reproduce `standup_data.json`,
+    /// not as a way to read the study.
+    pub const SLIDE_RULES: [Aggregation; 4] = [
+        Aggregation::Mean,
+        Aggregation::First,
+        Aggregation::Worst,
+        Aggregation::Last,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Aggregation::Mean => "Mean",
+            Aggregation::First => "First",
+            Aggregation::Worst => "Worst",
+            Aggregation::Last => "Last",
+            Aggregation::Pooled => "Pooled",
+        }
+    }
+
+    /// The rule in words, for the slide.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Aggregation::Mean => "mean of visit means",
+            Aggregation::First => "first visit of each phase",
+            Aggregation::Worst => "worst reading of each phase",
+            Aggregation::Last => "last visit of each phase",
+            Aggregation::Pooled => "mean of all readings",
+        }
+    }
+
+    /// Parses a [`Aggregation::label`], ignoring case.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.label().eq_ignore_ascii_case(name))
+    }
+
+    /// Applies the rule to the values of each visit, in time order. Visits
+    /// without a value are skipped; `None` if no visit has one.
+    pub fn apply(&self, visits: &[Vec<f64>], worst: Worst) -> Option<f64> {
+        let mut means = visits
+            .iter()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.iter().sum::<f64>() / v.len() as f64);
+        match self {
+            Aggregation::Mean => {
+                let means: Vec<f64> = means.collect();
+                (!means.is_empty()).then(|| means.iter().sum::<f64>() / means.len() as f64)
+            }
+            Aggregation::First => means.next(),
+            Aggregation::Last => means.next_back(),
+            Aggregation::Pooled => {
+                let values: Vec<f64> = visits.iter().flatten().copied().collect();
+                (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+            }
+            Aggregation::Worst => {
+                let values = visits.iter().flatten().copied();
+                match worst {
+                    Worst::Lowest => values.reduce(f64::min),
+                    Worst::Highest => values.reduce(f64::max),
+                }
+            }
+        }
+    }
+}
+
+fn slot(position: Position) -> usize {
+    match position {
+        Position::Lying => 0,
+        Position::Sitting => 1,
+        Position::Standing => 2,
+    }
+}
+
+/// Each patient's value per phase and position under `rule`, averaged over
+/// the patients who have one: `[pre, post]`, each `[lying, sitting,
+/// standing]`. `value` picks the measure out of a reading.
+pub fn position_profile(
+    records: &[BpRecord],
+    rule: Aggregation,
+    worst: Worst,
+    value: impl Fn(&BpRecord) -> Option<i32>,
+) -> [[Option<f64>; 3]; 2] {
+    // Per patient and phase, per position, the values of each visit.
+    let mut patients: BTreeMap<(&str, bool), [Vec<Vec<f64>>; 3]> = BTreeMap::new();
+    for visit in visits(records) {
+        let positions = patients
+            .entry((visit.mrn, visit.phase == Phase::Post))
+            .or_default();
+        for values in positions.iter_mut() {
+            values.push(Vec::new());
+        }
+        for r in &visit.readings {
+            if let (Some(v), Some(values)) = (value(r), positions[slot(r.pos)].last_mut()) {
+                values.push(f64::from(v));
+            }
+        }
+    }
+
+    let mut sums = [[(0.0, 0); 3]; 2];
+    for ((_, post), positions) in &patients {
+        for (i, visits) in positions.iter().enumerate() {
+            if let Some(v) = rule.apply(visits, worst) {
+                let (sum, count) = &mut sums[usize::from(*post)][i];
+                *sum += v;
+                *count += 1;
+            }
+        }
+    }
+    sums.map(|phase| phase.map(|(sum, count)| (count > 0).then(|| sum / f64::from(count))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_on_known_visits() {
+        let visits = [vec![1.0, 2.0, 3.0], vec![], vec![10.0]];
+        assert_eq!(Aggregation::Mean.apply(&visits, Worst::Highest), Some(6.0));
+        assert_eq!(Aggregation::First.apply(&visits, Worst::Highest), Some(2.0));
+        assert_eq!(Aggregation::Last.apply(&visits, Worst::Highest), Some(10.0));
+        assert_eq!(
+            Aggregation::Worst.apply(&visits, Worst::Highest),
+            Some(10.0)
+        );
+        assert_eq!(Aggregation::Worst.apply(&visits, Worst::Lowest), Some(1.0));
+        assert_eq!(
+            Aggregation::Pooled.apply(&visits, Worst::Highest),
+            Some(4.0)
+        );
+        for rule in Aggregation::ALL {
+            assert_eq!(rule.apply(&[vec![]], Worst::Lowest), None);
+            assert_eq!(Aggregation::parse(&rule.label().to_lowercase()), Some(rule));
+        }
+    }
+
+    #[test]
+    fn the_slide_does_not_offer_pooled() {
+        assert!(!Aggregation::SLIDE_RULES.contains(&Aggregation::Pooled));
+        assert!(Aggregation::SLIDE_RULES.contains(&Aggregation::default()));
+        assert_eq!(Aggregation::parse("pooled"), Some(Aggregation::Pooled));
+    }
+}
@@ -0,0 +1,159 @@
+//! Reads the study workbook (ODS or XLSX) directly, so the deck can be
+//! rebuilt from the source spreadsheet instead of hand-exported CSVs.
+//!
+//! Each dataset lives on its own sheet, found by name. Tabs that were never
+//! renamed are still found by their header row, as long as exactly one sheet
+//! has all of the dataset's required columns. A sheet comes back as CSV text
+//! for the usual loader, so validation and line numbers are the same
+//! whichever source the data came from.
+//!
+//! This module only depends on external crates: `build.rs` includes it to
+//! read the workbook before de-identification.
+
+use calamine::{open_workbook_auto_from_rs, Data, DataType, Range, Reader, Sheets};
+use std::fmt;
+use std::io::Cursor;
+
+/// Sheet names for the datasets.
+pub const SHEET_BP: &str = "BP";
+pub const SHEET_MEQ: &str = "MEQ";
+pub const SHEET_CGI: &str = "CGI-I";
+pub const SHEET_SUMMARY: &str = "BP summary";
+pub const SHEET_PRESCRIPTIONS: &str = "Prescriptions";
+pub const SHEET_EQUIVALENTS: &str = "MEQ equivalents";
+pub const SHEET_PROCEDURES: &str = "Procedures";
+
+/// The file extensions read as workbooks rather than CSV.
+pub const EXTENSIONS: &[&str] = &["ods", "xlsx", "xlsm", "xls"];
+
+/// Same layout as `RECORDED_TIME` in the CSV exports.
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkbookError {
+    /// Not a spreadsheet calamine can open, or a sheet that fails to parse.
+    Unreadable(String),
+    /// No sheet by that name, and no single sheet with the right headers.
+    NoSheet {
+        name: String,
+        available: Vec<String>,
+    },
+}
+
+impl fmt::Display for WorkbookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkbookError::Unreadable(reason) => write!(f, "{reason}"),
+            WorkbookError::NoSheet { name, available } => write!(
+                f,
+                "no sheet named '{name}' (sheets: {})",
+                available.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WorkbookError {}
+
+/// True if `file_name` has one of the workbook [`EXTENSIONS`].
+pub fn is_workbook(file_name: &str) -> bool {
+    file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+pub struct Workbook {
+    sheets: Sheets<Cursor<Vec<u8>>>,
+}
+
+impl Workbook {
+    pub fn open(bytes: Vec<u8>) -> Result<Self, WorkbookError> {
+        open_workbook_auto_from_rs(Cursor::new(bytes))
+            .map(|sheets| Self { sheets })
+            .map_err(|e| WorkbookError::Unreadable(e.to_string()))
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.sheet_names()
+    }
+
+    fn range(&mut self, name: &str) -> Result<Range<Data>, WorkbookError> {
+        self.sheets
+            .worksheet_range(name)
+            .map_err(|e| WorkbookError::Unreadable(format!("sheet '{name}': {e}")))
+    }
+
+    /// The sheet for a dataset: the one called `name` (ignoring case), or
+    /// else the only one whose first row has every column in `required`.
+    pub fn find_sheet(&mut self, name: &str, required: &[&str]) -> Result<String, WorkbookError> {
+        let names = self.sheet_names();
+        if let Some(found) = names.iter().find(|n| n.trim().eq_ignore_ascii_case(name)) {
+            return Ok(found.clone());
+        }
+        let mut matching = Vec::new();
+        for sheet in &names {
+            let range = self.range(sheet)?;
+            let headers: Vec<String> = range
+                .rows()
+                .next()
+                .map(|row| {
+                    row.iter()
+                        .map(|c| cell_text(c).trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            if required.iter().all(|col| headers.iter().any(|h| h == col)) {
+                matching.push(sheet.clone());
+            }
+        }
+        match matching.as_slice() {
+            [only] => Ok(only.clone()),
+            _ => Err(WorkbookError::NoSheet {
+                name: name.to_string(),
+                available: names,
+            }),
+        }
+    }
+
+    /// The sheet found by [`Workbook::find_sheet`], as CSV text. Rows keep
+    /// their sheet row numbers as CSV line numbers.
+    pub fn sheet_csv(&mut self, name: &str, required: &[&str]) -> Result<String, WorkbookError> {
+        let sheet = self.find_sheet(name, required)?;
+        let range = self.range(&sheet)?;
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(Vec::new());
+        // Rows above the first used cell are not in the range; keep them as
+        // blank lines so row numbers still match the sheet.
+        let skipped = range.start().map_or(0, |(row, _)| row);
+        let mut text = "\n".repeat(skipped as usize);
+        for row in range.rows() {
+            writer
+                .write_record(row.iter().map(cell_text))
+                .map_err(|e| WorkbookError::Unreadable(e.to_string()))?;
+        }
+        let csv = writer
+            .into_inner()
+            .map_err(|e| WorkbookError::Unreadable(e.to_string()))?;
+        text.push_str(&String::from_utf8_lossy(&csv));
+        Ok(text)
+    }
+}
+
+/// A cell as the CSV exports would show it: whole numbers without a
+/// decimal point and dates in the `RECORDED_TIME` layout.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) => s.clone(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        Data::Float(f) => f.to_string(),
+        Data::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Data::DateTime(_) | Data::DateTimeIso(_) => cell
+            .as_datetime()
+            .map_or_else(|| cell.to_string(), |t| t.format(TIME_FORMAT).to_string()),
+        Data::DurationIso(s) => s.clone(),
+        Data::Error(e) => e.to_string(),
+    }
+}
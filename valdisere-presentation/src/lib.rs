@@ -0,0 +1,4 @@
+//! Data handling for the STANDUP deck, shared by the app and the tools in
+//! `src/bin`.
+
+pub mod data;
@@ -3,37 +3,38 @@
 use dioxus::prelude::*;
 
 pub mod components;
+pub use valdisere_presentation::data;
+pub mod registry;
 pub mod slides;
 
 // Use built-in Asset system if relevant, or just use string paths for simplicty in Dioxus 0.6+
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
-#[derive(Debug, Clone, Routable, PartialEq)]
-#[rustfmt::skip]
-pub enum Route {
-    #[layout(AppLayout)]
-        #[route("/")]
-        Intro {},
-        #[route("/motivation")]
-        Motivation {},
-        #[route("/background")]
-        Background {},
-        #[route("/rationale")]
-        Rationale {},
-        #[route("/methods")]
-        Methods {},
-        #[route("/results")]
-        Results {},
-        #[route("/discussion")]
-        Discussion {},
-        #[route("/future")]
-        Future {},
-        #[route("/video")]
-        CaseVideo {},
-    #[end_layout]
-    // 404 handler
-    #[route("/:..route")]
-    PageNotFound { route: Vec<String> },
+// The single source of truth for the deck: order, paths, titles and navbar
+// labels. Adding a slide here is all that is needed to route it, list it in
+// the navbar and place it in the prev/next sequence.
+registry::deck! {
+    #[derive(Debug, Clone, Routable, PartialEq)]
+    layout: AppLayout,
+    routes: [
+        #[route("/presenter")]
+        Presenter {},
+        #[route("/patient/:id")]
+        PatientView { id: usize },
+        #[route("/:..route")]
+        PageNotFound { route: Vec<String> },
+    ],
+    slides: [
+        Intro { path: "/", title: "The STANDUP Study", nav: "Home", section: Opening, visibility: Nav, notes: slides::intro::NOTES },
+        Motivation { path: "/motivation", title: "Motivation", nav: "Motivation", section: Opening, visibility: Nav, notes: slides::motivation::NOTES },
+        Background { path: "/background", title: "Background", nav: "Background", section: Science, visibility: Nav, notes: slides::background::NOTES },
+        Rationale { path: "/rationale", title: "Scientific Rationale", nav: "Rationale", section: Science, visibility: Nav, notes: slides::rationale::NOTES },
+        Methods { path: "/methods", title: "Methodology", nav: "Methods", section: Study, visibility: Nav, notes: slides::methods::NOTES },
+        Results { path: "/results", title: "Study Results", nav: "Results", section: Study, visibility: Nav, notes: slides::results::NOTES },
+        Discussion { path: "/discussion", title: "Discussion", nav: "Discussion", section: Closing, visibility: Nav, notes: slides::discussion::NOTES },
+        Future { path: "/future", title: "Future Directions", nav: "Future", section: Closing, visibility: Deck, notes: slides::future::NOTES },
+        CaseVideo { path: "/video", title: "Case Study", nav: "Case Video", section: Closing, visibility: Nav, notes: slides::video::NOTES },
+    ],
 }
 
 fn main() {
@@ -42,6 +43,10 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    // Above the router so the Results slide and the patient pages share it.
+    components::live_data::use_live_data_provider();
+    components::live_data::use_live_data_sync();
+
     rsx! {
         // Inject Tailwind CSS
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
@@ -51,7 +56,13 @@ fn App() -> Element {
 
 #[component]
 fn AppLayout() -> Element {
+    let route = use_route::<Route>();
+    let title = registry::slide_for(&route).map_or("STANDUP II", |s| s.title);
+    let position = use_context_provider(|| Signal::new(DeckPosition::default()));
+    let slide = registry::slide_index(&route);
+
     rsx! {
+        document::Title { "{title}" }
         div { class: "min-h-screen w-full bg-zinc-950 text-slate-200 font-sans selection:bg-brand-orange/30 selection:text-brand-orange flex flex-col",
             // Navigation
             components::navbar::NavBar {}
@@ -59,13 +70,25 @@ fn AppLayout() -> Element {
             // Main Content Area
             // pt-16 accounts for the fixed navbar height
             main { class: "pt-16 flex-1 flex flex-col",
-                Outlet::<Route> {}
+                if let Some(slide) = slide {
+                    FragmentScope { key: "{slide}", slide, step: position().on(slide).step,
+                        Outlet::<Route> {}
+                    }
+                } else {
+                    Outlet::<Route> {}
+                }
             }
+
+            // Prev/next buttons plus keyboard and presentation-clicker navigation
+            components::slide_deck::NavControls {}
         }
     }
 }
 
 // Temporary placeholders for slides (will be moved to modules)
+use crate::components::fragments::{DeckPosition, FragmentScope};
+use crate::components::patient::PatientView;
+use crate::components::presenter::Presenter;
 use crate::slides::background::Background;
 use crate::slides::discussion::Discussion;
 use crate::slides::future::Future;
@@ -82,8 +105,8 @@ fn PageNotFound(route: Vec<String>) -> Element {
         div { class: "h-screen flex items-center justify-center",
             div { class: "text-center",
                 h1 { class: "text-4xl font-bold text-brand-orange mb-4", "404" }
-                p { class: "text-zinc-400", "Page not found" }
-                Link { to: Route::Intro {}, class: "mt-8 inline-block px-6 py-2 bg-brand-orange text-brand-dark rounded-full font-bold", "Go Home" }
+                p { class: "text-zinc-400", "No slide at /{route.join(\"/\")}" }
+                Link { to: registry::first_slide().route.clone(), class: "mt-8 inline-block px-6 py-2 bg-brand-orange text-brand-dark rounded-full font-bold", "Go Home" }
             }
         }
     }
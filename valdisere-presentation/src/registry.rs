@@ -0,0 +1,202 @@
+//! Declarative slide registry.
+//!
+//! Every slide is declared exactly once in the `deck!` invocation in `main.rs`.
+//! The macro expands that list into the `Route` enum, the `SLIDES` table and
+//! the 404 catch-all, so routing, the navbar and prev/next ordering are all
+//! read from the same source and cannot drift apart.
+
+use crate::Route;
+
+/// Which part of the talk a slide belongs to. The navbar draws a divider
+/// whenever the section changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Opening,
+    Science,
+    Study,
+    Closing,
+}
+
+/// Where a slide shows up besides its own route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Part of the deck sequence and listed in the navbar.
+    Nav,
+    /// Part of the deck sequence but not listed in the navbar.
+    Deck,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slide {
+    pub route: Route,
+    pub path: &'static str,
+    pub title: &'static str,
+    pub nav_label: &'static str,
+    pub section: Section,
+    pub visibility: Visibility,
+    /// Speaker notes, shown only in the presenter view. Each slide module
+    /// keeps its own `NOTES` next to the component.
+    pub notes: &'static str,
+}
+
+impl Slide {
+    pub fn in_nav(&self) -> bool {
+        self.visibility == Visibility::Nav
+    }
+}
+
+/// Expands a list of slides into the `Route` enum and the `SLIDES` table.
+///
+/// Each entry names the slide component (which doubles as the route variant)
+/// and its metadata. The order of entries is the presentation order. Routes
+/// that are not slides (such as the presenter view and the 404 catch-all,
+/// which must come last) go in `routes` and are placed outside the layout.
+///
+/// The enum's attributes, including `#[derive(Routable)]`, are given at the
+/// call site: the derive binds route parameters by name, and those bindings
+/// only see fields declared in the same hygiene context as the derive.
+macro_rules! deck {
+    (
+        $(#[$attr:meta])*
+        layout: $layout:ident,
+        routes: [ $($extra:tt)* ],
+        slides: [
+            $(
+                $variant:ident {
+                    path: $path:literal,
+                    title: $title:literal,
+                    nav: $nav:literal,
+                    section: $section:ident,
+                    visibility: $visibility:ident,
+                    notes: $notes:expr $(,)?
+                }
+            ),+ $(,)?
+        ] $(,)?
+    ) => {
+        $(#[$attr])*
+        #[rustfmt::skip]
+        pub enum Route {
+            #[layout($layout)]
+            $(
+                #[route($path)]
+                $variant {},
+            )+
+            #[end_layout]
+            $($extra)*
+        }
+
+        const _: () = assert!(
+            $crate::registry::paths_unique(&[$($path),+]),
+            "two slides share the same path"
+        );
+
+        pub static SLIDES: &[$crate::registry::Slide] = &[
+            $(
+                $crate::registry::Slide {
+                    route: Route::$variant {},
+                    path: $path,
+                    title: $title,
+                    nav_label: $nav,
+                    section: $crate::registry::Section::$section,
+                    visibility: $crate::registry::Visibility::$visibility,
+                    notes: $notes,
+                },
+            )+
+        ];
+
+        /// Renders the slide component for `route` outside of the router
+        /// outlet, e.g. for the presenter view's current and next previews.
+        pub fn render_slide(route: &Route) -> Element {
+            match route {
+                $(Route::$variant {} => rsx! { $variant {} },)+
+                _ => VNode::empty(),
+            }
+        }
+    };
+}
+
+pub(crate) use deck;
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Compile-time check used by `deck!` so a copy-pasted entry cannot shadow
+/// another slide.
+pub const fn paths_unique(paths: &[&str]) -> bool {
+    let mut i = 0;
+    while i < paths.len() {
+        let mut j = i + 1;
+        while j < paths.len() {
+            if str_eq(paths[i], paths[j]) {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Position of `route` in the deck, or `None` for routes that are not slides.
+pub fn slide_index(route: &Route) -> Option<usize> {
+    crate::SLIDES.iter().position(|s| &s.route == route)
+}
+
+pub fn slide_for(route: &Route) -> Option<&'static Slide> {
+    slide_index(route).map(|i| &crate::SLIDES[i])
+}
+
+pub fn first_slide() -> &'static Slide {
+    &crate::SLIDES[0]
+}
+
+/// Slides listed in the navbar, in deck order.
+pub fn nav_slides() -> impl Iterator<Item = &'static Slide> {
+    crate::SLIDES.iter().filter(|s| s.in_nav())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render_slide, SLIDES};
+    use dioxus::prelude::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn routes_round_trip() {
+        for (i, slide) in SLIDES.iter().enumerate() {
+            assert_eq!(slide.route.to_string(), slide.path);
+            assert_eq!(Route::from_str(slide.path).ok(), Some(slide.route.clone()));
+            assert_eq!(slide_index(&slide.route), Some(i));
+        }
+    }
+
+    /// A slide outside the router, with the context every slide may read.
+    #[component]
+    fn Harness(index: usize) -> Element {
+        crate::components::live_data::use_live_data_provider();
+        render_slide(&SLIDES[index].route)
+    }
+
+    #[test]
+    fn every_slide_renders() {
+        for (index, slide) in SLIDES.iter().enumerate() {
+            let mut dom = VirtualDom::new_with_props(Harness, HarnessProps { index });
+            dom.rebuild_in_place();
+            let html = dioxus_ssr::render(&dom);
+            assert!(html.starts_with('<'), "{} rendered nothing", slide.path);
+        }
+    }
+}
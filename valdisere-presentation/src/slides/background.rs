@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Bradbury & Eggleston described postural hypotension in 1925 and we still treat it with the same few drugs. Point to the Medicare Part D spend on midodrine, fludrocortisone and droxidopa.";
+
 #[component]
 pub fn Background() -> Element {
     // Try both absolute and relative paths for debugging, but in Dioxus + Assets, usually "bradbury.png" if served from root or "/assets/bradbury.png"
@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Relieving venous obstruction tracks with better orthostatic stability. Stress that mechanical obstruction has to be separated from pure autonomic failure when selecting patients.";
+
 #[component]
 pub fn Discussion() -> Element {
     rsx! {
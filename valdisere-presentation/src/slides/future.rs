@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Next step is a randomised trial with a sham arm to isolate placebo effects on subjective symptom scores.";
+
 #[component]
 pub fn Future() -> Element {
     rsx! {
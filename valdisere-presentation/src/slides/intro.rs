@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Welcome and thanks to the organisers. Introduce INA and the STANDUP programme: venous stenting for orthostatic hypotension and intolerance.";
+
 #[component]
 pub fn Intro() -> Element {
     rsx! {
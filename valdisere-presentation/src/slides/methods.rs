@@ -1,5 +1,8 @@
+use crate::components::fragments::Fragment;
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Three steps: full arterial and venous diagnostics with IVUS, angioplasty and stenting sized to the vessel, then neuro checks and follow-up at 2 weeks, 3 and 6 months, 1 and 2 years.";
+
 #[component]
 pub fn Methods() -> Element {
     rsx! {
@@ -18,7 +21,7 @@ pub fn Methods() -> Element {
 
             div { class: "grid grid-cols-3 gap-6 z-10 h-full pb-8",
                 // Step 1: Diagnostics
-                div { class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors animate-fade-in-up delay-100",
+                Fragment { step: 1, class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 animate-fade-in-up delay-100",
                     div { class: "text-5xl font-black text-brand-green/40 mb-4", "01" }
                     h3 { class: "text-xl font-bold text-brand-orange mb-4", "Comprehensive Diagnostics" }
                     ul { class: "space-y-3 text-sm text-brand-light",
@@ -31,7 +34,7 @@ pub fn Methods() -> Element {
                 }
 
                 // Step 2: Intervention
-                div { class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors animate-fade-in-up delay-300",
+                Fragment { step: 2, class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 animate-fade-in-up delay-300",
                     div { class: "text-5xl font-black text-brand-green/40 mb-4", "02" }
                     h3 { class: "text-xl font-bold text-brand-orange mb-4", "Venous Intervention" }
                     ul { class: "space-y-3 text-sm text-brand-light",
@@ -46,7 +49,7 @@ pub fn Methods() -> Element {
                 }
 
                 // Step 3: Safety & Follow-up
-                div { class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors animate-fade-in-up delay-500",
+                Fragment { step: 3, class: "flex flex-col bg-brand-green/10 p-6 rounded-2xl border border-brand-green/30 hover:border-brand-orange/50 animate-fade-in-up delay-500",
                     div { class: "text-5xl font-black text-brand-green/40 mb-4", "03" }
                     h3 { class: "text-xl font-bold text-brand-orange mb-4", "Safety & Longitudinal Care" }
                      ul { class: "space-y-3 text-sm text-brand-light",
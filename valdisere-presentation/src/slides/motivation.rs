@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "OH is common and disabling, yet diagnosis leans on symptoms and one-off readings. Our ask: an objective marker of venous outflow obstruction so we stent the right patients.";
+
 #[component]
 pub fn Motivation() -> Element {
     rsx! {
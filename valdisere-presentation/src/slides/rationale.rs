@@ -1,5 +1,8 @@
+use crate::components::fragments::Fragment;
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Walk through the five mechanisms in order. Spend the most time on glymphatic clearance: it links venous obstruction to metabolite build-up in autonomic centres.";
+
 #[component]
 pub fn Rationale() -> Element {
     rsx! {
@@ -17,19 +20,19 @@ pub fn Rationale() -> Element {
 
                 // Left Column: Core Regulations
                 div { class: "space-y-6",
-                    div { class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors",
+                    Fragment { step: 1, class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50",
                         h3 { class: "text-xl font-semibold text-brand-orange mb-2", "1. Baroreflex & Metabolite Clearance" },
                         p { class: "text-brand-taupe text-sm leading-relaxed",
                             "Efficiency depends on proper metabolite clearance (Fick Principle). Impaired venous outflow disrupts this, exacerbating OH."
                         }
                     }
-                     div { class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors",
+                     Fragment { step: 2, class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50",
                         h3 { class: "text-xl font-semibold text-brand-orange mb-2", "2. Venous Return Dynamics" },
                         p { class: "text-brand-taupe text-sm leading-relaxed",
                              "Gravity causes pooling (>500ml). Venous stenosis impedes return, dropping Cardiac Output >20%."
                         }
                     }
-                     div { class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors",
+                     Fragment { step: 3, class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50",
                         h3 { class: "text-xl font-semibold text-brand-orange mb-2", "3. Static Venous Tone" },
                         p { class: "text-brand-taupe text-sm leading-relaxed",
                              "Venous tone fails to adjust: Excessive supine constriction (Hypertension) vs Inadequate standing constriction (Hypotension)."
@@ -39,13 +42,13 @@ pub fn Rationale() -> Element {
 
                 // Right Column: Advanced Mechanisms
                 div { class: "space-y-6",
-                    div { class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50 transition-colors",
+                    Fragment { step: 4, class: "p-6 bg-brand-green/10 rounded-xl border border-brand-green/30 hover:border-brand-orange/50",
                          h3 { class: "text-xl font-semibold text-brand-orange mb-2", "4. Vestibulo-Sympathetic Reflex" },
                          p { class: "text-brand-taupe text-sm leading-relaxed",
                              "Venous congestion impairs key sympathetic pathways, leading to 'Sympathetic Anticipation Failure' prior to standing."
                          }
                     }
-                    div { class: "p-6 bg-gradient-to-br from-brand-green/20 to-brand-orange/10 rounded-xl border border-brand-orange/40 shadow-lg transform scale-105",
+                    Fragment { step: 5, class: "p-6 bg-gradient-to-br from-brand-green/20 to-brand-orange/10 rounded-xl border border-brand-orange/40 shadow-lg transform scale-105",
                         h3 { class: "text-xl font-bold text-brand-orange mb-2", "5. Glymphatic Clearance" },
                          p { class: "text-brand-light text-sm leading-relaxed",
                              "Venous engorgement constricts perivenous spaces, impeding interstitial fluid drainage. Links venous obstruction to neurotoxic metabolite accumulation in autonomic centers."
@@ -1,344 +1,1040 @@
+use crate::components::chart::{
+    theme, BarChart, Marker, PairedLine, PairedLinePlot, ScatterPlot, ScatterPoint, Series,
+    TimelineBand, TimelineChart, TimelinePoint, TimelineSeries, WaterfallBar, WaterfallChart,
+};
+use crate::components::diagnostics::DiagnosticsPanel;
+use crate::components::inclusion::InclusionPanel;
+use crate::components::live_data::{use_live_data, DataDropZone, DataSourceBadge};
+use crate::data::cgi::{self, CgiSummary, CrossTab};
+use crate::data::cohort::Cohort;
+use crate::data::hemodynamics::{
+    diastolic_pairs, hr_increment_pairs, orthostatic_drops, paired_drops, systolic_pairs,
+    DropStats, MechanismCounts, PatientDrop, NEUROGENIC_RATIO,
+};
+use crate::data::medication::{
+    MeqResponse, ResponseCounts, DISCONTINUED_REDUCTION, RESPONDER_REDUCTION,
+};
+use crate::data::orthostatic::{OhSummary, Prevalence, Transition};
+use crate::data::outcomes::{join, Correlation, PatientOutcome};
+use crate::data::records::MeqRecord;
+use crate::data::stats::{format_p, median, PairedSummary, TestResult};
+use crate::data::timepoints::{follow_up, Timepoint, TimepointSummary, PERIPROCEDURAL_DAYS};
+use crate::data::visits::{
+    position_profile, Aggregation, Worst, STAND_TEST_GAP_MINUTES, VISIT_GAP_HOURS,
+};
+use crate::Route;
 use dioxus::prelude::*;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
-const CSV_BP: &str = include_str!("../../assets/standup_results.csv");
-const CSV_MEQ: &str = include_str!("../../assets/standup_results_meq.csv");
-const CSV_CGI: &str = include_str!("../../assets/standup_results_cgi.csv");
+pub const NOTES: &str = "Start with hemodynamics and OH prevalence, then medication burden, then clinical impression (CGI-I). Pause on the systolic drop before and after stenting.";
 
 #[derive(Debug, Clone, PartialEq, Copy)]
 enum Tab {
     Hemodynamics,
+    Orthostatic,
+    Patients,
     Medication,
     Clinical,
+    Correlation,
+    FollowUp,
 }
 
-// --- Hemodynamics Types ---
-#[derive(Debug, Clone, PartialEq)]
-enum Position {
-    Lying,
-    Sitting,
-    Standing,
-    Unknown,
-}
+#[component]
+pub fn Results() -> Element {
+    let mut active_tab = use_signal(|| Tab::Hemodynamics);
+    let mut show_diagnostics = use_signal(|| false);
+    let mut show_inclusion = use_signal(|| false);
+    let mut selected_patient = use_signal(|| None::<usize>);
+    let mut rule = use_signal(Aggregation::default);
 
-#[derive(Debug, Clone, PartialEq)]
-enum Phase {
-    Pre,
-    Post,
-    Unknown,
-}
+    // -- Data Memos --
+    let live = use_live_data();
+    let datasets = use_memo(move || live.read().datasets.clone());
+    let data_summaries = use_memo(move || datasets.read().summaries());
+    let rejected_rows: usize = data_summaries.read().iter().map(|d| d.rejected.len()).sum();
+    let consistency = use_memo(move || datasets.read().consistency());
+    let meq_check = use_memo(move || datasets.read().meq_check());
+    let missingness = use_memo(move || datasets.read().missingness());
+    let needs_attention = rejected_rows > 0
+        || !consistency.read().is_consistent()
+        || meq_check
+            .read()
+            .as_ref()
+            .is_some_and(|c| !c.is_consistent());
+    let cohort = use_memo(move || datasets.read().cohort());
+    let (n_total, n_meq, n_cgi) = {
+        let c = cohort.read();
+        (c.n_total(), c.n_meq(), c.n_cgi())
+    };
 
-#[derive(Debug, Clone, PartialEq)]
-struct BpRecord {
-    sys: Option<i32>,
-    hr: Option<i32>,
-    pos: Position,
-    phase: Phase,
-}
+    // -- Hemodynamic Aggregation, one value per patient under the rule --
+    let bp_profile = use_memo(move || {
+        let records = &datasets.read().bp.records;
+        (
+            position_profile(records, rule(), Worst::Lowest, |r| r.sys),
+            position_profile(records, rule(), Worst::Lowest, |r| r.dia),
+            position_profile(records, rule(), Worst::Highest, |r| r.hr),
+        )
+    });
+    let ([pre_sys, post_sys], [pre_dia, post_dia], [pre_hr, post_hr]) = *bp_profile.read();
 
-#[derive(Default, Clone, Copy, PartialEq)]
-struct BpStats {
-    sys_sum: i32,
-    sys_count: i32,
-    hr_sum: i32,
-    hr_count: i32,
-}
-impl BpStats {
-    fn avg_sys(&self) -> i32 {
-        if self.sys_count > 0 {
-            self.sys_sum / self.sys_count
-        } else {
-            0
+    // -- Orthostatic Drop, paired per patient --
+    let patient_drops =
+        use_memo(move || paired_drops(&orthostatic_drops(&datasets.read().bp.records)));
+    let drop_stats = use_memo(move || {
+        let patients = patient_drops.read();
+        (
+            PairedSummary::from_pairs(systolic_pairs(&patients, rule())),
+            PairedSummary::from_pairs(diastolic_pairs(&patients, rule())),
+            PairedSummary::from_pairs(hr_increment_pairs(&patients, rule())),
+        )
+    });
+    let (sys_drop, dia_drop, hr_rise) = *drop_stats.read();
+    let sd_text = sys_drop
+        .change_sd
+        .map_or("n/a".to_string(), |sd| format!("{sd:.1}"));
+    // A drop is shown as the change on standing, so a rise reads "+5".
+    let drop_text = |v: Option<f64>| v.map_or("n/a".to_string(), |v| format!("{:+.0} mmHg", -v));
+    let change_text = |v: Option<f64>| v.map_or("n/a".to_string(), |v| format!("{v:+.1} mmHg"));
+    let mechanisms = use_memo(move || {
+        let patients = patient_drops.read();
+        (
+            MechanismCounts::count(patients.iter().map(|p| &p.pre), rule()),
+            MechanismCounts::count(patients.iter().map(|p| &p.post), rule()),
+        )
+    });
+    let (pre_mechanism, post_mechanism) = *mechanisms.read();
+    let oh_summary = use_memo(move || OhSummary::build(&datasets.read().bp.records));
+
+    // -- MEQ Aggregation --
+    let meq_stats = use_memo(move || {
+        let data = datasets.read();
+        let recs = &data.meq.records;
+        let total_pre: f32 = recs.iter().map(|r| r.pre).sum();
+        let total_post: f32 = recs.iter().map(|r| r.post).sum();
+        let count = recs.len() as f32;
+        (count > 0.0).then(|| (total_pre / count, total_post / count))
+    });
+    let meq_test = use_memo(move || {
+        PairedSummary::from_pairs(
+            datasets
+                .read()
+                .meq
+                .records
+                .iter()
+                .map(|r| (f64::from(r.pre), f64::from(r.post))),
+        )
+    });
+    let meq_test = *meq_test.read();
+    // Undefined, not zero, when no patient was on medication before.
+    let meq_reduction = meq_stats()
+        .filter(|&(pre, _)| pre > 0.0)
+        .map_or("n/a".to_string(), |(pre, post)| {
+            format!("{:.1}%", (pre - post) / pre * 100.0)
+        });
+
+    // -- CGI-I, on its own and against the other outcomes --
+    let cgi = use_memo(move || {
+        let data = datasets.read();
+        let records = &data.cgi.records;
+        let patients = patient_drops.read();
+        let hemodynamic: BTreeMap<&str, bool> = patients
+            .iter()
+            .filter_map(|p| {
+                let (pre, post) = p.pre.systolic(rule()).zip(p.post.systolic(rule()))?;
+                Some((p.pre.mrn.as_str(), post < pre))
+            })
+            .collect();
+        let medication: BTreeMap<&str, bool> = data
+            .meq
+            .records
+            .iter()
+            .map(|r| (r.mrn.as_str(), MeqResponse::classify(r).is_responder()))
+            .collect();
+        (
+            CgiSummary::build(records),
+            vec![
+                CrossTab::build(records, "Smaller systolic drop", &hemodynamic),
+                CrossTab::build(
+                    records,
+                    &format!("MEQ ≥{RESPONDER_REDUCTION}% reduction"),
+                    &medication,
+                ),
+            ],
+        )
+    });
+    let (cgi_summary, cgi_tabs) = cgi();
+
+    // -- Follow-up windows --
+    let procedure_dates = use_memo(move || datasets.read().procedure_dates());
+    let timepoints =
+        use_memo(move || follow_up(&datasets.read().bp.records, &procedure_dates.read(), rule()));
+
+    // -- All three outcomes per patient --
+    let outcomes = use_memo(move || {
+        let data = datasets.read();
+        join(
+            &patient_drops.read(),
+            &data.meq.records,
+            &data.cgi.records,
+            rule(),
+        )
+    });
+
+    rsx! {
+        DataDropZone {
+            div { class: "flex flex-col min-h-full w-full bg-brand-dark text-brand-light p-8",
+
+                // Header with Tabs
+                div { class: "z-10 mb-6 flex justify-between items-end animate-fade-in-down",
+                    div {
+                         h1 { class: "text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-brand-orange to-orange-400 mb-4",
+                            "Study Results"
+                        }
+                        div { class: "flex gap-2",
+                            TabButton { active: active_tab() == Tab::Hemodynamics, label: "Hemodynamics", onclick: move |_| active_tab.set(Tab::Hemodynamics) }
+                            TabButton { active: active_tab() == Tab::Orthostatic, label: "OH Prevalence", onclick: move |_| active_tab.set(Tab::Orthostatic) }
+                            TabButton { active: active_tab() == Tab::Patients, label: "Per Patient", onclick: move |_| active_tab.set(Tab::Patients) }
+                            TabButton { active: active_tab() == Tab::Medication, label: "Medication", onclick: move |_| active_tab.set(Tab::Medication) }
+                            TabButton { active: active_tab() == Tab::Clinical, label: "Clinical Outcomes", onclick: move |_| active_tab.set(Tab::Clinical) }
+                            TabButton { active: active_tab() == Tab::Correlation, label: "Correlation", onclick: move |_| active_tab.set(Tab::Correlation) }
+                            TabButton { active: active_tab() == Tab::FollowUp, label: "Follow-up", onclick: move |_| active_tab.set(Tab::FollowUp) }
+                        }
+                    }
+                    div { class: "text-right pb-2 flex items-center gap-3",
+                        DataSourceBadge {}
+                        button {
+                            class: "text-brand-taupe text-sm hover:text-brand-light cursor-pointer",
+                            title: "Patient inclusion",
+                            onclick: move |_| show_inclusion.toggle(),
+                            "STANDUP Cohort N={n_total}"
+                        }
+                        // Deliberately faint: the data diagnostics are for rehearsal, not the audience.
+                        button {
+                            class: "w-6 h-6 rounded-full text-xs text-brand-taupe border border-white/10 opacity-20 hover:opacity-100 transition-all cursor-pointer",
+                            title: "Data diagnostics",
+                            onclick: move |_| show_diagnostics.toggle(),
+                            if needs_attention { "!" } else { "i" }
+                        }
+                    }
+                }
+
+                if show_diagnostics() {
+                    DiagnosticsPanel { datasets: data_summaries(), consistency: consistency(), meq: meq_check(), missing: missingness(), on_close: move |_| show_diagnostics.set(false) }
+                }
+                if show_inclusion() {
+                    InclusionPanel { cohort: cohort(), on_close: move |_| show_inclusion.set(false) }
+                }
+
+                if matches!(active_tab(), Tab::Hemodynamics | Tab::Patients | Tab::Clinical | Tab::Correlation | Tab::FollowUp) {
+                    RuleSelector { rule: rule(), on_change: move |r| rule.set(r) }
+                }
+
+                // Tab Content
+                div { class: "flex-1 overflow-visible animate-fade-in-up",
+                    match active_tab() {
+                        Tab::Hemodynamics => rsx! {
+                            div { class: "grid grid-cols-12 gap-8 h-full pb-4",
+                                // Key Metrics
+                                 div { class: "col-span-3 flex flex-col gap-6",
+                                     div { class: "p-6 bg-brand-green/10 rounded-2xl border border-brand-green/30 space-y-4",
+                                        h3 { class: "text-lg font-bold text-brand-orange uppercase", "Systolic Drop" }
+                                        div { class: "text-sm text-brand-taupe", "Supine to Standing, per patient: {rule().describe()}" }
+                                        Denominator { n: sys_drop.n, total: n_total, of: "with stand tests pre and post" }
+                                        div { class: "flex flex-col gap-1",
+                                            span { class: "text-xs text-brand-taupe uppercase", "Pre-Intervention" }
+                                            span { class: "text-4xl font-mono font-bold text-red-400", "{drop_text(sys_drop.pre)}" }
+                                            span { class: "text-sm font-mono text-brand-taupe", "diastolic {drop_text(dia_drop.pre)}" }
+                                        }
+                                        div { class: "w-full h-px bg-brand-green/20" }
+                                        div { class: "flex flex-col gap-1",
+                                            span { class: "text-xs text-brand-taupe uppercase", "Post-Intervention" }
+                                            span { class: "text-4xl font-mono font-bold text-brand-green", "{drop_text(sys_drop.post)}" }
+                                            span { class: "text-sm font-mono text-brand-taupe", "diastolic {drop_text(dia_drop.post)}" }
+                                        }
+                                        div { class: "w-full h-px bg-brand-green/20" }
+                                        div { class: "flex flex-col gap-1",
+                                            span { class: "text-xs text-brand-taupe uppercase", "Paired Change" }
+                                            span { class: "text-2xl font-mono font-bold text-brand-light", "{change_text(sys_drop.change)}" }
+                                            span { class: "text-sm font-mono text-brand-taupe", "SD {sd_text} · diastolic {change_text(dia_drop.change)}" }
+                                        }
+                                    }
+                                    div { class: "p-6 bg-brand-green/5 rounded-2xl border border-brand-green/10 space-y-3",
+                                        p { class: "text-sm text-brand-taupe italic",
+                                            if !sys_drop.significant() {
+                                                "Summary: No significant change in the orthostatic drop."
+                                            } else if sys_drop.change.is_some_and(|c| c < 0.0) {
+                                                "Summary: Significant hemodynamic stabilization achieved."
+                                            } else {
+                                                "Summary: Orthostatic drop significantly larger after the intervention."
+                                            }
+                                        }
+                                        TestReadout { summary: sys_drop, unit: "mmHg" }
+                                    }
+                                }
+                                // Graphs
+                                div { class: "col-span-9 grid grid-cols-2 gap-6",
+                                    BarChart { title: "Systolic BP Profile", unit: "mmHg",
+                                        categories: position_labels(),
+                                        series: vec![
+                                            Series::partial("Pre-Op", pre_sys.to_vec(), theme::PRE),
+                                            Series::partial("Post-Op", post_sys.to_vec(), theme::POST),
+                                        ],
+                                    }
+                                    BarChart { title: "Diastolic BP Profile", unit: "mmHg",
+                                        categories: position_labels(),
+                                        series: vec![
+                                            Series::partial("Pre-Op", pre_dia.to_vec(), theme::PRE),
+                                            Series::partial("Post-Op", post_dia.to_vec(), theme::POST),
+                                        ],
+                                    }
+                                    BarChart { title: "Heart Rate Profile", unit: "bpm",
+                                        categories: position_labels(),
+                                        series: vec![
+                                            Series::partial("Pre-Op", pre_hr.to_vec(), theme::PRE),
+                                            Series::partial("Post-Op", post_hr.to_vec(), theme::POST),
+                                        ],
+                                    }
+                                    ResponsePanel {
+                                        diastolic: dia_drop,
+                                        heart_rate: hr_rise,
+                                        pre: pre_mechanism,
+                                        post: post_mechanism,
+                                    }
+                                }
+                            }
+                        },
+                        Tab::Orthostatic => rsx! {
+                            OhPanel { summary: oh_summary(), n_total }
+                        },
+                        Tab::Patients => rsx! {
+                            SpaghettiPanel {
+                                patients: patient_drops(),
+                                rule: rule(),
+                                cohort: cohort(),
+                                n_total,
+                                selected: selected_patient(),
+                                on_select: move |number| {
+                                    let current = *selected_patient.peek();
+                                    selected_patient.set(if current == Some(number) { None } else { Some(number) });
+                                },
+                            }
+                        },
+                        Tab::Medication => rsx! {
+                             div { class: "grid grid-cols-2 gap-12 h-full items-center px-12",
+                                div { class: "flex flex-col gap-8",
+                                    div { class: "text-6xl font-bold text-brand-light",
+                                        "{meq_reduction}"
+                                        span { class: "text-3xl text-brand-green block mt-2", "Reduction in Medication" }
+                                    }
+                                    p { class: "text-xl text-brand-taupe leading-relaxed",
+                                        if meq_test.significant() && meq_test.change.is_some_and(|c| c < 0.0) {
+                                            "Midodrine Equivalent Dose (MEQ) significantly decreased post-intervention, indicating reduced pharmaceutical dependence."
+                                        } else {
+                                            "The change in Midodrine Equivalent Dose (MEQ) post-intervention was not statistically significant."
+                                        }
+                                    }
+                                    Denominator { n: n_meq, total: n_total, of: "with pre/post MEQ" }
+                                    TestReadout { summary: meq_test, unit: "ME" }
+                                }
+                                MeqWaterfall { records: datasets.read().meq.records.clone(), cohort: cohort() }
+                            }
+                        },
+                        Tab::Clinical => rsx! {
+                             div { class: "flex flex-col gap-8 h-full px-8",
+                                div { class: "text-center",
+                                    h2 { class: "text-2xl text-brand-light font-bold mb-2", "Clinical Global Impression - Improvement (CGI-I)" }
+                                    p { class: "text-brand-taupe", "1 = Very Much Improved, 2 = Much Improved" }
+                                    Denominator { n: n_cgi, total: n_total, of: "rated" }
+                                }
+                                div { class: "grid grid-cols-3 gap-8 items-start",
+                                    div { class: "col-span-2",
+                                        BarChart { title: "CGI-I Scores",
+                                            categories: cgi::SCORES.map(|score| format!("{score}\n{}", cgi::label(score))).collect(),
+                                            series: vec![Series::new("Patients", cgi_summary.counts.iter().map(|&n| n as f64).collect(), theme::PRE)],
+                                        }
+                                    }
+                                    CgiPanel { summary: cgi_summary, tabs: cgi_tabs }
+                                }
+                            }
+                        },
+                        Tab::Correlation => rsx! {
+                            CorrelationPanel { outcomes: outcomes(), cohort: cohort(), n_total }
+                        },
+                        Tab::FollowUp => rsx! {
+                            FollowUpPanel {
+                                summaries: timepoints(),
+                                estimated: procedure_dates.read().estimated(),
+                                dated: procedure_dates.read().len(),
+                            }
+                        },
+                    }
+                }
+            }
         }
     }
-    fn avg_hr(&self) -> i32 {
-        if self.hr_count > 0 {
-            self.hr_sum / self.hr_count
-        } else {
-            0
+}
+
+/// Every patient's mean orthostatic systolic drop before and after, one line
+/// each, so the patients who did not improve stay visible. Clicking a line
+/// opens that patient's detail alongside, which links to their own page.
+#[component]
+fn SpaghettiPanel(
+    patients: Vec<PatientDrop>,
+    rule: Aggregation,
+    cohort: Cohort,
+    n_total: usize,
+    selected: Option<usize>,
+    on_select: EventHandler<usize>,
+) -> Element {
+    let numbered: Vec<(usize, &PatientDrop)> = patients
+        .iter()
+        .filter_map(|p| Some((cohort.patient_number(&p.pre.mrn)?, p)))
+        .collect();
+    let lines: Vec<PairedLine> = numbered
+        .iter()
+        .filter_map(|(number, p)| {
+            let (before, after) = p.pre.systolic(rule).zip(p.post.systolic(rule))?;
+            Some(PairedLine {
+                key: *number,
+                before,
+                after,
+                // A smaller drop after the procedure is an improvement.
+                color: match after.total_cmp(&before) {
+                    Ordering::Less => theme::IMPROVED,
+                    Ordering::Equal => theme::GREEN,
+                    Ordering::Greater => theme::WORSENED,
+                },
+            })
+        })
+        .collect();
+    let improved = lines.iter().filter(|l| l.after < l.before).count();
+    let unchanged = lines.iter().filter(|l| l.after == l.before).count();
+    let worsened = lines.len() - improved - unchanged;
+    let befores: Vec<f64> = lines.iter().map(|l| l.before).collect();
+    let afters: Vec<f64> = lines.iter().map(|l| l.after).collect();
+    let detail = selected.and_then(|n| numbered.iter().find(|(number, _)| *number == n).copied());
+
+    rsx! {
+        div { class: "grid grid-cols-12 gap-8 h-full pb-4",
+            div { class: "col-span-8 flex flex-col gap-2",
+                Denominator { n: lines.len(), total: n_total, of: "with stand tests pre and post" }
+                PairedLinePlot {
+                    title: "Orthostatic Systolic Drop per Patient",
+                    columns: ("Pre-Intervention".to_string(), "Post-Intervention".to_string()),
+                    unit: "mmHg",
+                    median: median(&befores).zip(median(&afters)),
+                    legend: vec![
+                        (format!("Smaller drop ({improved})"), theme::IMPROVED),
+                        (format!("Unchanged ({unchanged})"), theme::GREEN),
+                        (format!("Larger drop ({worsened})"), theme::WORSENED),
+                        ("Median".to_string(), theme::TAUPE),
+                    ],
+                    lines,
+                    selected,
+                    on_select: move |key| on_select.call(key),
+                }
+            }
+            div { class: "col-span-4",
+                if let Some((number, patient)) = detail {
+                    PatientDetail { number, patient: patient.clone(), rule }
+                } else {
+                    div { class: "h-full flex items-center justify-center p-6 rounded-2xl border border-white/10 text-brand-taupe text-sm",
+                        "Click a line for that patient's detail."
+                    }
+                }
+            }
         }
     }
 }
 
-// --- Medication Types ---
-#[derive(Debug, Clone, PartialEq)]
-struct MeqRecord {
-    pre: f32,
-    post: f32,
-}
+#[component]
+fn PatientDetail(number: usize, patient: PatientDrop, rule: Aggregation) -> Element {
+    let fmt = |v: Option<f64>| v.map_or("n/a".to_string(), |v| format!("{v:.1} mmHg"));
+    let hr = |v: Option<f64>| v.map_or("n/a".to_string(), |v| format!("{v:+.1} bpm on standing"));
+    let change = patient
+        .pre
+        .systolic(rule)
+        .zip(patient.post.systolic(rule))
+        .map_or("n/a".to_string(), |(a, b)| format!("{:+.1} mmHg", b - a));
+    let span = |stats: &DropStats| {
+        stats.span.map_or(String::new(), |(first, last)| {
+            format!("{} to {}", first.format("%b %Y"), last.format("%b %Y"))
+        })
+    };
 
-// --- CGI Types ---
-#[derive(Debug, Clone, PartialEq)]
-struct CgiRecord {
-    score: i32,
+    rsx! {
+        div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 space-y-4",
+            h3 { class: "text-2xl font-bold text-brand-orange", "Patient {number}" }
+            for (label, stats) in [("Pre-Intervention", &patient.pre), ("Post-Intervention", &patient.post)] {
+                div { class: "space-y-1",
+                    span { class: "text-xs text-brand-taupe uppercase", "{label}" }
+                    div { class: "text-2xl font-mono font-bold text-brand-light", "{fmt(stats.systolic(rule))}" }
+                    div { class: "text-xs font-mono text-brand-taupe",
+                        "diastolic {fmt(stats.diastolic(rule))} · {stats.tests()} stand tests in {stats.visits()} visits · {span(stats)}"
+                    }
+                    div { class: "text-xs font-mono text-brand-taupe",
+                        "HR {hr(stats.hr_increment(rule))}"
+                        if let Some(mechanism) = stats.mechanism(rule) {
+                            " · {mechanism.label()} pattern"
+                        }
+                    }
+                }
+            }
+            div { class: "w-full h-px bg-brand-green/20" }
+            div { class: "space-y-1",
+                span { class: "text-xs text-brand-taupe uppercase", "Change in systolic drop" }
+                div { class: "text-2xl font-mono font-bold text-brand-light", "{change}" }
+            }
+            Link { to: Route::PatientView { id: number }, class: "inline-block text-sm text-brand-orange hover:text-brand-light",
+                "All readings →"
+            }
+        }
+    }
 }
 
-// --- Parsers ---
-fn parse_int(s: &str) -> Option<i32> {
-    s.trim().parse::<i32>().ok()
-}
-fn parse_float(s: &str) -> Option<f32> {
-    s.trim().parse::<f32>().ok()
-}
+/// Each patient's change in MEQ, sorted from the largest increase to the
+/// largest reduction, with the responder and discontinuation thresholds.
+/// Clicking a bar opens that patient's page.
+#[component]
+fn MeqWaterfall(records: Vec<MeqRecord>, cohort: Cohort) -> Element {
+    let nav = use_navigator();
+    let counts = ResponseCounts::tally(&records);
+    let color = |response: MeqResponse| match response {
+        MeqResponse::Discontinued => theme::IMPROVED,
+        MeqResponse::Halved => theme::TAUPE,
+        MeqResponse::Reduced | MeqResponse::Unchanged => theme::GREEN,
+        MeqResponse::Increased | MeqResponse::Started => theme::WORSENED,
+    };
+    let mut bars: Vec<WaterfallBar> = records
+        .iter()
+        .map(|r| {
+            let response = MeqResponse::classify(r);
+            // Plotted as percent change, so reductions hang below zero. A
+            // patient starting from zero has no percentage and is drawn at
+            // the top of the scale.
+            let (value, label) = match r.percent_reduction() {
+                Some(reduction) => (-reduction, format!("{:.0}", -reduction)),
+                None if response == MeqResponse::Started => (100.0, "new".to_string()),
+                None => (0.0, "0".to_string()),
+            };
+            WaterfallBar {
+                // Without a patient number there is no page to open.
+                key: cohort.patient_number(&r.mrn),
+                value,
+                label,
+                color: color(response),
+            }
+        })
+        .collect();
+    bars.sort_by(|a, b| b.value.total_cmp(&a.value));
+    let legend = [
+        MeqResponse::Discontinued,
+        MeqResponse::Halved,
+        MeqResponse::Reduced,
+        MeqResponse::Increased,
+    ]
+    .map(|r| (r.label().to_string(), color(r)))
+    .to_vec();
 
-fn parse_bp_data(content: &str) -> Vec<BpRecord> {
-    content
-        .lines()
-        .skip(1)
-        .filter_map(|line| {
-            let cols: Vec<&str> = line.split(',').collect();
-            if cols.len() < 7 {
-                return None;
+    rsx! {
+        div { class: "flex flex-col gap-4",
+            WaterfallChart {
+                title: "Change in MEQ per Patient",
+                unit: "%",
+                bars,
+                thresholds: vec![
+                    (-RESPONDER_REDUCTION, "≥50% reduction".to_string()),
+                    (-DISCONTINUED_REDUCTION, "Discontinued".to_string()),
+                ],
+                legend,
+                on_select: move |id| {
+                    nav.push(Route::PatientView { id });
+                },
             }
+            div { class: "grid grid-cols-3 gap-4 text-center",
+                div { class: "p-4 rounded-xl bg-zinc-900/50 border border-white/10",
+                    div { class: "text-3xl font-mono font-bold text-brand-light", "{counts.responders()} / {counts.total()}" }
+                    div { class: "text-xs uppercase tracking-widest text-brand-taupe", "≥50% reduction" }
+                }
+                div { class: "p-4 rounded-xl bg-zinc-900/50 border border-white/10",
+                    div { class: "text-3xl font-mono font-bold text-brand-light", "{counts.discontinued}" }
+                    div { class: "text-xs uppercase tracking-widest text-brand-taupe", "Discontinued" }
+                }
+                div { class: "p-4 rounded-xl bg-zinc-900/50 border border-white/10",
+                    div { class: "text-3xl font-mono font-bold text-brand-orange", "{counts.increased + counts.started}" }
+                    div { class: "text-xs uppercase tracking-widest text-brand-taupe", "Increased or started" }
+                }
+            }
+        }
+    }
+}
 
-            let sys = parse_int(cols[2]);
-            let hr = parse_int(cols[4]);
+/// The cohort across the protocol's follow-up windows: how many patients
+/// were assessed in each, and the mean orthostatic drop of those with a
+/// stand test, one point per window.
+#[component]
+fn FollowUpPanel(summaries: Vec<TimepointSummary>, estimated: usize, dated: usize) -> Element {
+    let at = |i: usize| i as f64;
+    let series = |name: &str, color: &'static str, value: fn(&TimepointSummary) -> Option<f64>| {
+        TimelineSeries {
+            name: name.to_string(),
+            points: summaries
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| {
+                    Some(TimelinePoint {
+                        x: at(i),
+                        value: value(s)?,
+                        run: 0,
+                        marker: Marker::Circle,
+                    })
+                })
+                .collect(),
+            color,
+        }
+    };
+    let trajectory = vec![
+        series("Systolic drop", theme::PRE, |s| s.sys_drop),
+        series("Diastolic drop", theme::POST, |s| s.dia_drop),
+    ];
+    let bands: Vec<TimelineBand> = summaries
+        .iter()
+        .enumerate()
+        .map(|(i, s)| TimelineBand {
+            from: at(i) - 0.5,
+            to: at(i) + 0.5,
+            label: s.timepoint.label().to_string(),
+            shaded: s.timepoint == Timepoint::Baseline,
+        })
+        .collect();
+    let x_labels: Vec<(f64, String)> = summaries
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (at(i), format!("n = {}", s.stand_tested)))
+        .collect();
 
-            let pos = match cols[5].trim().to_lowercase().as_str() {
-                "lying" => Position::Lying,
-                "sitting" => Position::Sitting,
-                "standing" => Position::Standing,
-                _ => Position::Unknown,
-            };
+    rsx! {
+        div { class: "flex flex-col gap-6",
+            div { class: "grid grid-cols-2 gap-6",
+                BarChart { title: "Patients Assessed",
+                    categories: summaries.iter().map(|s| s.timepoint.label().to_string()).collect(),
+                    series: vec![
+                        Series::new("Any reading", summaries.iter().map(|s| s.assessed as f64).collect(), theme::PRE),
+                        Series::new("Stand test", summaries.iter().map(|s| s.stand_tested as f64).collect(), theme::POST),
+                    ],
+                }
+                div { class: "flex flex-col gap-2",
+                    TimelineChart {
+                        title: "Orthostatic Drop by Timepoint",
+                        unit: "mmHg",
+                        series: trajectory,
+                        bands,
+                        x_labels,
+                    }
+                    // Every window depends on the procedure date, so an
+                    // estimate belongs with the chart, not under the panel.
+                    if estimated > 0 {
+                        div { class: "px-4 py-2 rounded-lg bg-brand-orange/20 border border-brand-orange/50 text-sm text-brand-orange",
+                            "Procedure date estimated for {estimated} of {dated} patients from their readings, so their windows are approximate."
+                        }
+                    }
+                }
+            }
+            div { class: "text-xs font-mono text-brand-taupe",
+                "Days 0–{PERIPROCEDURAL_DAYS} after the procedure are a window of their own; the others are centred on the scheduled visits (2 wk, 3 mo, 6 mo, 1 yr, 2 yr). "
+                if estimated == 0 {
+                    "Procedure dates on file for all {dated} patients."
+                }
+            }
+        }
+    }
+}
 
-            let phase = if cols[6].to_lowercase().contains("pre") {
-                Phase::Pre
-            } else if cols[6].to_lowercase().contains("post") {
-                Phase::Post
-            } else {
-                Phase::Unknown
-            };
+/// Point colour by CGI-I band: responders, little or no change, worse, and
+/// patients without a rating.
+fn cgi_color(score: Option<i32>) -> &'static str {
+    match score {
+        Some(score) if cgi::is_responder(score) => theme::IMPROVED,
+        Some(1..=4) => theme::GREEN,
+        Some(_) => theme::WORSENED,
+        None => theme::TAUPE,
+    }
+}
 
-            if pos == Position::Unknown || phase == Phase::Unknown {
-                return None;
-            }
-            Some(BpRecord {
-                sys,
-                hr,
-                pos,
-                phase,
+/// Whether the patients whose orthostatic drop shrank are also the ones who
+/// came off their pressors: systolic improvement against MEQ reduction, one
+/// point per patient coloured by CGI-I, with Spearman's rho and a fitted
+/// line. Clicking a point opens that patient's page.
+#[component]
+fn CorrelationPanel(outcomes: Vec<PatientOutcome>, cohort: Cohort, n_total: usize) -> Element {
+    let nav = use_navigator();
+    let both: Vec<(&PatientOutcome, f64, f64)> = outcomes
+        .iter()
+        .filter_map(|o| Some((o, o.meq_reduction?, o.sys_improvement?)))
+        .collect();
+    let main = Correlation::of(&both.iter().map(|&(_, x, y)| (x, y)).collect::<Vec<_>>());
+    let points: Vec<ScatterPoint> = both
+        .iter()
+        .filter_map(|&(o, x, y)| {
+            Some(ScatterPoint {
+                key: cohort.patient_number(&o.mrn)?,
+                x,
+                y,
+                color: cgi_color(o.cgi),
             })
         })
-        .collect()
-}
+        .collect();
+    let with_cgi = |value: fn(&PatientOutcome) -> Option<f64>| {
+        let pairs: Vec<(f64, f64)> = outcomes
+            .iter()
+            .filter_map(|o| Some((f64::from(o.cgi?), value(o)?)))
+            .collect();
+        Correlation::of(&pairs)
+    };
+    let rows = [
+        ("Systolic vs MEQ", main),
+        ("CGI-I vs systolic", with_cgi(|o| o.sys_improvement)),
+        ("CGI-I vs MEQ", with_cgi(|o| o.meq_reduction)),
+    ];
+    let legend = vec![
+        ("CGI-I 1–2".to_string(), theme::IMPROVED),
+        ("CGI-I 3–4".to_string(), theme::GREEN),
+        ("CGI-I 5–7".to_string(), theme::WORSENED),
+        ("Not rated".to_string(), theme::TAUPE),
+    ];
 
-fn parse_meq_data(content: &str) -> Vec<MeqRecord> {
-    content
-        .lines()
-        .skip(1)
-        .filter_map(|line| {
-            let cols: Vec<&str> = line.split(',').collect();
-            if cols.len() < 4 {
-                return None;
+    rsx! {
+        div { class: "grid grid-cols-3 gap-8 items-start",
+            div { class: "col-span-2",
+                ScatterPlot {
+                    title: "Systolic Improvement vs MEQ Reduction",
+                    x_label: "MEQ reduction (%)",
+                    unit: "mmHg",
+                    points,
+                    line: main.fit.map(|f| (f.intercept, f.slope)),
+                    legend,
+                    on_select: move |id| {
+                        nav.push(Route::PatientView { id });
+                    },
+                }
             }
-            let pre = parse_float(cols[2])?;
-            let post = parse_float(cols[3])?;
-            Some(MeqRecord { pre, post })
-        })
-        .collect()
-}
-
-fn parse_cgi_data(content: &str) -> Vec<CgiRecord> {
-    content
-        .lines()
-        .skip(1)
-        .filter_map(|line| {
-            let cols: Vec<&str> = line.split(',').collect();
-            if cols.len() < 3 {
-                return None;
+            div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 flex flex-col gap-4 text-sm",
+                div { class: "text-xs text-brand-taupe",
+                    "Improvement is the pre minus the post orthostatic systolic drop; positive is better on both axes. A lower CGI-I is better, so agreement shows as a negative rho."
+                }
+                Denominator { n: main.n, total: n_total, of: "with BP and MEQ pre and post" }
+                for (label, c) in rows {
+                    div { class: "space-y-1",
+                        h3 { class: "text-lg font-bold text-brand-orange uppercase", "{label}" }
+                        div { class: "font-mono text-brand-light",
+                            if let Some(rho) = c.spearman {
+                                "Spearman ρ = {rho.statistic:.2}, {format_p(rho.p)}"
+                            } else {
+                                "n/a"
+                            }
+                        }
+                        div { class: "text-xs font-mono text-brand-taupe", "n = {c.n}" }
+                    }
+                }
+                if let Some(fit) = main.fit {
+                    div { class: "text-xs font-mono text-brand-taupe",
+                        "Fitted line: {fit.slope:+.3} mmHg per % of MEQ, {fit.intercept:+.1} mmHg at no reduction"
+                    }
+                }
             }
-            let score = parse_int(cols[2])?;
-            Some(CgiRecord { score })
-        })
-        .collect()
+        }
+    }
 }
 
+/// Pre/post OH prevalence by the consensus criterion, and how individual
+/// patients moved between the two.
 #[component]
-pub fn Results() -> Element {
-    let mut active_tab = use_signal(|| Tab::Hemodynamics);
+fn OhPanel(summary: OhSummary, n_total: usize) -> Element {
+    let paired = summary.patients.len() - summary.unpaired();
+    let cell = |t: Transition| {
+        let count = summary.transitions(t);
+        rsx! {
+            td { class: "p-4 text-center",
+                div { class: "text-4xl font-mono font-bold text-brand-light", "{count}" }
+                div { class: "text-xs uppercase tracking-widest text-brand-taupe", "{t.label()}" }
+            }
+        }
+    };
 
-    // -- Data Memos --
-    let bp_data = use_memo(|| parse_bp_data(CSV_BP));
-    let meq_data = use_memo(|| parse_meq_data(CSV_MEQ));
-    let cgi_data = use_memo(|| parse_cgi_data(CSV_CGI));
-
-    // -- Hemodynamic Aggregation --
-    let bp_stats = use_memo(move || {
-        let mut pre = [BpStats::default(); 3];
-        let mut post = [BpStats::default(); 3];
-        for r in bp_data.read().iter() {
-            let idx = match r.pos {
-                Position::Lying => 0,
-                Position::Sitting => 1,
-                Position::Standing => 2,
-                _ => continue,
-            };
-            let target = match r.phase {
-                Phase::Pre => &mut pre[idx],
-                Phase::Post => &mut post[idx],
-                _ => continue,
-            };
-            if let Some(s) = r.sys {
-                target.sys_sum += s;
-                target.sys_count += 1;
+    rsx! {
+        div { class: "grid grid-cols-12 gap-8 h-full px-8",
+            div { class: "col-span-5 flex flex-col gap-6",
+                p { class: "text-brand-taupe",
+                    "Orthostatic hypotension: systolic drop ≥20 mmHg or diastolic drop ≥10 mmHg within 3 minutes of standing, per measurement session."
+                }
+                PrevalenceTile { label: "Pre-Intervention", prevalence: summary.pre, class: "text-red-400" }
+                PrevalenceTile { label: "Post-Intervention", prevalence: summary.post, class: "text-brand-green" }
             }
-            if let Some(h) = r.hr {
-                target.hr_sum += h;
-                target.hr_count += 1;
+            div { class: "col-span-7 flex flex-col gap-4",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "Transitions" }
+                Denominator { n: paired, total: n_total, of: "classified before and after" }
+                table { class: "w-full border border-white/10 rounded-2xl bg-zinc-900/50",
+                    thead {
+                        tr { class: "text-xs uppercase text-brand-taupe",
+                            th { class: "p-3" }
+                            th { class: "p-3", "Post: OH" }
+                            th { class: "p-3", "Post: No OH" }
+                        }
+                    }
+                    tbody {
+                        tr { class: "border-t border-white/10",
+                            th { class: "p-3 text-xs uppercase text-brand-taupe text-left", "Pre: OH" }
+                            {cell(Transition::Persisted)}
+                            {cell(Transition::Resolved)}
+                        }
+                        tr { class: "border-t border-white/10",
+                            th { class: "p-3 text-xs uppercase text-brand-taupe text-left", "Pre: No OH" }
+                            {cell(Transition::New)}
+                            {cell(Transition::Never)}
+                        }
+                    }
+                }
+                if summary.unpaired() > 0 {
+                    p { class: "text-xs text-brand-taupe",
+                        "{summary.unpaired()} patients with readings in both phases are indeterminate in at least one and are not counted above."
+                    }
+                }
             }
         }
-        (pre, post)
-    });
-    let (pre_bp, post_bp) = *bp_stats.read();
-    let pre_drop = pre_bp[0].avg_sys() - pre_bp[2].avg_sys();
-    let post_drop = post_bp[0].avg_sys() - post_bp[2].avg_sys();
+    }
+}
 
-    // -- MEQ Aggregation --
-    let meq_stats = use_memo(move || {
-        let recs = meq_data.read();
-        let total_pre: f32 = recs.iter().map(|r| r.pre).sum();
-        let total_post: f32 = recs.iter().map(|r| r.post).sum();
-        let count = recs.len() as f32;
-        if count == 0.0 {
-            return (0.0, 0.0);
+#[component]
+fn PrevalenceTile(label: &'static str, prevalence: Prevalence, class: &'static str) -> Element {
+    let rate = prevalence
+        .patients
+        .rate()
+        .map_or("n/a".to_string(), |r| format!("{:.0}%", r * 100.0));
+    rsx! {
+        div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 space-y-2",
+            span { class: "text-xs text-brand-taupe uppercase", "{label}" }
+            div { class: "text-5xl font-mono font-bold {class}", "{rate}" }
+            div { class: "text-sm text-brand-taupe",
+                "{prevalence.patients.oh} of {prevalence.patients.determinate} patients with OH"
+            }
+            div { class: "text-xs font-mono text-brand-taupe",
+                "Visits: {prevalence.visits.oh} OH of {prevalence.visits.determinate} classified, {prevalence.visits.indeterminate} indeterminate"
+            }
+            div { class: "text-xs font-mono text-brand-taupe",
+                "Sessions: {prevalence.sessions.oh} OH of {prevalence.sessions.determinate} classified, {prevalence.sessions.indeterminate} indeterminate"
+            }
         }
-        (total_pre / count, total_post / count)
+    }
+}
+
+fn position_labels() -> Vec<String> {
+    ["Lying", "Sitting", "Standing"].map(String::from).to_vec()
+}
+
+/// The patient count behind a tab's figures, out of the whole cohort.
+#[component]
+fn Denominator(n: usize, total: usize, of: &'static str) -> Element {
+    rsx! {
+        div { class: "text-xs font-mono text-brand-taupe", "n = {n} of {total} patients {of}" }
+    }
+}
+
+/// The tests behind a pre/post comparison, in the small print under a claim.
+#[component]
+fn TestReadout(summary: PairedSummary, unit: &'static str) -> Element {
+    let line =
+        |label: &str, test: Option<TestResult>| test.map(|t| format!("{label} {}", format_p(t.p)));
+    let tests = [
+        line("Wilcoxon", summary.wilcoxon),
+        line("paired t", summary.t_test),
+        line("sign test", summary.sign),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" · ");
+    let ci = summary.change.zip(summary.ci).map(|(change, ci)| {
+        format!(
+            "Mean change {change:+.1} {unit}, 95% CI {:.1} to {:.1}",
+            ci.lower, ci.upper
+        )
     });
-    let (avg_meq_pre, avg_meq_post) = *meq_stats.read();
-    let meq_reduction = if avg_meq_pre > 0.0 {
-        ((avg_meq_pre - avg_meq_post) / avg_meq_pre) * 100.0
-    } else {
-        0.0
+    let ci_boot = summary
+        .ci_boot
+        .map(|ci| format!("Bootstrap 95% CI {:.1} to {:.1}", ci.lower, ci.upper));
+    let effect = match (summary.cohens_dz, summary.rank_biserial) {
+        (Some(dz), Some(r)) => Some(format!("d_z = {dz:.2}, rank-biserial r = {r:.2}")),
+        (Some(dz), None) => Some(format!("d_z = {dz:.2}")),
+        (None, Some(r)) => Some(format!("rank-biserial r = {r:.2}")),
+        (None, None) => None,
     };
 
-    // -- CGI Aggregation --
-    let cgi_dist = use_memo(move || {
-        let mut counts = [0; 8]; // 1-7 (score 0 unused)
-        for r in cgi_data.read().iter() {
-            if r.score >= 1 && r.score <= 7 {
-                counts[r.score as usize] += 1;
+    rsx! {
+        div { class: "text-xs font-mono text-brand-taupe space-y-1",
+            div { "{tests}" }
+            if let Some(ci) = ci {
+                div { "{ci}" }
+            }
+            if let Some(ci_boot) = ci_boot {
+                div { "{ci_boot}" }
+            }
+            if let Some(effect) = effect {
+                div { "{effect}" }
             }
         }
-        counts
-    });
-    let cgi_counts = *cgi_dist.read();
+    }
+}
 
-    rsx! {
-        div { class: "flex flex-col min-h-full w-full bg-brand-dark text-brand-light p-8",
+/// The diastolic drop and the heart rate response, beside the systolic
+/// numbers they qualify. The ΔHR/ΔSBP split only covers patients whose
+/// systolic drop reaches the OH threshold in that phase.
+#[component]
+fn ResponsePanel(
+    diastolic: PairedSummary,
+    heart_rate: PairedSummary,
+    pre: MechanismCounts,
+    post: MechanismCounts,
+) -> Element {
+    let p = |summary: &PairedSummary| {
+        summary
+            .wilcoxon
+            .map_or(String::new(), |t| format!(" · Wilcoxon {}", format_p(t.p)))
+    };
+    let change = |summary: &PairedSummary, sign: &str, unit: &str| match summary {
+        PairedSummary {
+            pre: Some(pre),
+            post: Some(post),
+            change: Some(change),
+            ..
+        } => format!("{sign}{pre:.1} → {sign}{post:.1} {unit} ({change:+.1})"),
+        _ => "n/a".to_string(),
+    };
+    let diastolic_change = change(&diastolic, "-", "mmHg");
+    let hr_change = change(&heart_rate, "+", "bpm");
+    let split = |counts: MechanismCounts| {
+        let mut text = format!(
+            "{} neurogenic · {} non-neurogenic",
+            counts.neurogenic, counts.non_neurogenic
+        );
+        if counts.unknown > 0 {
+            text.push_str(&format!(" · {} without HR", counts.unknown));
+        }
+        text
+    };
 
-            // Header with Tabs
-            div { class: "z-10 mb-6 flex justify-between items-end animate-fade-in-down",
-                div {
-                     h1 { class: "text-4xl font-bold text-transparent bg-clip-text bg-gradient-to-r from-brand-orange to-orange-400 mb-4",
-                        "Study Results"
-                    }
-                    div { class: "flex gap-2",
-                        TabButton { active: active_tab() == Tab::Hemodynamics, label: "Hemodynamics", onclick: move |_| active_tab.set(Tab::Hemodynamics) }
-                        TabButton { active: active_tab() == Tab::Medication, label: "Medication", onclick: move |_| active_tab.set(Tab::Medication) }
-                        TabButton { active: active_tab() == Tab::Clinical, label: "Clinical Outcomes", onclick: move |_| active_tab.set(Tab::Clinical) }
-                    }
+    rsx! {
+        div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 flex flex-col gap-4 text-sm",
+            div { class: "space-y-1",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "Diastolic Drop" }
+                div { class: "font-mono text-brand-light",
+                    "{diastolic_change}"
                 }
-                div { class: "text-right pb-2",
-                    div { class: "text-brand-taupe text-sm", "STANDUP Cohort N=37" }
+                div { class: "text-xs font-mono text-brand-taupe", "n = {diastolic.n}{p(&diastolic)}" }
+            }
+            div { class: "space-y-1",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "HR Increment on Standing" }
+                div { class: "font-mono text-brand-light",
+                    "{hr_change}"
                 }
+                div { class: "text-xs font-mono text-brand-taupe", "n = {heart_rate.n}{p(&heart_rate)}" }
             }
+            div { class: "space-y-1",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "ΔHR / ΔSBP" }
+                div { class: "text-xs text-brand-taupe",
+                    "Patients with a systolic drop ≥20 mmHg; below {NEUROGENIC_RATIO} bpm/mmHg suggests neurogenic OH."
+                }
+                div { class: "font-mono text-brand-light", "Pre: {split(pre)}" }
+                div { class: "font-mono text-brand-light", "Post: {split(post)}" }
+            }
+        }
+    }
+}
 
-            // Tab Content
-            div { class: "flex-1 overflow-visible animate-fade-in-up",
-                match active_tab() {
-                    Tab::Hemodynamics => rsx! {
-                        div { class: "grid grid-cols-12 gap-8 h-full pb-4",
-                            // Key Metrics
-                             div { class: "col-span-3 flex flex-col gap-6",
-                                 div { class: "p-6 bg-brand-green/10 rounded-2xl border border-brand-green/30 space-y-4",
-                                    h3 { class: "text-lg font-bold text-brand-orange uppercase", "Systolic Drop" }
-                                    div { class: "text-sm text-brand-taupe", "Supine to Standing" }
-                                    div { class: "flex flex-col gap-1",
-                                        span { class: "text-xs text-brand-taupe uppercase", "Pre-Intervention" }
-                                        span { class: "text-4xl font-mono font-bold text-red-400", "-{pre_drop} mmHg" }
-                                    }
-                                    div { class: "w-full h-px bg-brand-green/20" }
-                                    div { class: "flex flex-col gap-1",
-                                        span { class: "text-xs text-brand-taupe uppercase", "Post-Intervention" }
-                                        span { class: "text-4xl font-mono font-bold text-brand-green", "-{post_drop} mmHg" }
-                                    }
-                                }
-                                div { class: "p-6 bg-brand-green/5 rounded-2xl border border-brand-green/10",
-                                     p { class: "text-sm text-brand-taupe italic", "Summary: Significant hemodynamic stabilization achieved." }
-                                }
-                            }
-                            // Graphs
-                            div { class: "col-span-9 grid grid-cols-2 gap-6",
-                                ChartBox { title: "Systolic BP Profile", color: "orange",
-                                    labels: vec!["Lying", "Sitting", "Standing"],
-                                    data_a: vec![pre_bp[0].avg_sys(), pre_bp[1].avg_sys(), pre_bp[2].avg_sys()],
-                                    data_b: vec![post_bp[0].avg_sys(), post_bp[1].avg_sys(), post_bp[2].avg_sys()],
-                                    legend: ("Pre-Op", "Post-Op"),
-                                    scale: 200.0
-                                }
-                                ChartBox { title: "Heart Rate Profile", color: "blue",
-                                    labels: vec!["Lying", "Sitting", "Standing"],
-                                    data_a: vec![pre_bp[0].avg_hr(), pre_bp[1].avg_hr(), pre_bp[2].avg_hr()],
-                                    data_b: vec![post_bp[0].avg_hr(), post_bp[1].avg_hr(), post_bp[2].avg_hr()],
-                                    legend: ("Pre-Op", "Post-Op"),
-                                    scale: 150.0
-                                }
+/// Responder rate with its exact CI, the median score, and CGI-I response
+/// against each other outcome as a 2×2 table.
+#[component]
+fn CgiPanel(summary: CgiSummary, tabs: Vec<CrossTab>) -> Element {
+    let responders = summary.responders.map_or("n/a".to_string(), |r| {
+        format!(
+            "{}/{} ({:.0}%, 95% CI {:.0}–{:.0}%)",
+            r.k,
+            r.n,
+            100.0 * r.rate(),
+            100.0 * r.ci.lower,
+            100.0 * r.ci.upper
+        )
+    });
+    let median = match (summary.median, summary.iqr) {
+        (Some(m), Some((q1, q3))) => format!("{m:.1} (IQR {q1:.1}–{q3:.1})"),
+        _ => "n/a".to_string(),
+    };
+
+    rsx! {
+        div { class: "p-6 bg-zinc-900/50 rounded-2xl border border-zinc-700/50 flex flex-col gap-4 text-sm",
+            div { class: "space-y-1",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "Responders" }
+                div { class: "text-xs text-brand-taupe", "CGI-I ≤{cgi::RESPONDER_MAX}; exact (Clopper-Pearson) interval." }
+                div { class: "font-mono text-brand-light", "{responders}" }
+            }
+            div { class: "space-y-1",
+                h3 { class: "text-lg font-bold text-brand-orange uppercase", "Median Score" }
+                div { class: "font-mono text-brand-light", "{median}" }
+            }
+            for tab in tabs {
+                div { class: "space-y-1",
+                    h3 { class: "text-lg font-bold text-brand-orange uppercase", "vs {tab.outcome}" }
+                    table { class: "font-mono text-brand-light text-xs",
+                        thead {
+                            tr { class: "text-brand-taupe",
+                                th { class: "pr-4 text-left font-normal", "" }
+                                th { class: "pr-4 font-normal", "Yes" }
+                                th { class: "font-normal", "No" }
                             }
                         }
-                    },
-                    Tab::Medication => rsx! {
-                         div { class: "grid grid-cols-2 gap-12 h-full items-center px-12",
-                            div { class: "flex flex-col gap-8",
-                                div { class: "text-6xl font-bold text-brand-light",
-                                    "{meq_reduction:.1}%"
-                                    span { class: "text-3xl text-brand-green block mt-2", "Reduction in Medication" }
-                                }
-                                p { class: "text-xl text-brand-taupe leading-relaxed",
-                                    "Midodrine Equivalent Dose (MEQ) significantly decreased post-intervention, indicating reduced pharmaceutical dependence."
-                                }
-                            }
-                            div {
-                                ChartBox { title: "Avg. Daily MEQ Dose", color: "green",
-                                    labels: vec!["Pre-Intervention", "Post-Intervention"],
-                                    data_a: vec![avg_meq_pre as i32, 0],
-                                    data_b: vec![0, avg_meq_post as i32],
-                                    legend: ("Pre (mg)", "Post (mg)"),
-                                    scale: (avg_meq_pre * 1.2) as f64
+                        tbody {
+                            for (row, [yes, no]) in ["Responder", "Non-responder"].into_iter().zip(tab.counts) {
+                                tr {
+                                    td { class: "pr-4 text-brand-taupe", "{row}" }
+                                    td { class: "pr-4 text-center", "{yes}" }
+                                    td { class: "text-center", "{no}" }
                                 }
                             }
                         }
-                    },
-                    Tab::Clinical => rsx! {
-                         div { class: "flex flex-col gap-8 h-full px-8",
-                            div { class: "text-center",
-                                h2 { class: "text-2xl text-brand-light font-bold mb-2", "Clinical Global Impression - Improvement (CGI-I)" }
-                                p { class: "text-brand-taupe", "1 = Very Much Improved, 2 = Much Improved" }
-                            }
-                            div { class: "flex-1 flex items-end justify-center gap-4 bg-zinc-900/50 rounded-2xl p-8 border border-zinc-700/50",
-                                {(1..=7).filter(|s| cgi_counts[*s] > 0).map(|score| {
-                                    let count = cgi_counts[score as usize];
-                                    let max = *cgi_counts.iter().max().unwrap_or(&1) as f64;
-                                    let h_pct = (count as f64 / max) * 100.0;
-                                    let label = match score { 1 => "Very Much Improved", 2 => "Much Improved", 3 => "Minimally Improved", 4 => "No Change", _ => "" };
-                                    rsx! {
-                                        div { class: "flex flex-col items-center gap-2 w-32 group",
-                                            div { class: "relative w-full bg-brand-green/20 rounded-t-lg transition-all group-hover:bg-brand-green/40 min-h-[10px]", style: "height: {h_pct}%",
-                                                div { class: "absolute -top-8 left-1/2 -translate-x-1/2 font-bold text-2xl text-brand-light", "{count}" }
-                                            }
-                                            div { class: "text-center text-sm font-bold text-brand-orange", "{score}" }
-                                            div { class: "text-center text-xs text-brand-taupe", "{label}" }
-                                        }
-                                    }
-                                })}
-                            }
-                        }
-                    },
+                    }
+                    div { class: "text-xs font-mono text-brand-taupe",
+                        "{tab.concordant()} of {tab.total()} concordant"
+                    }
                 }
             }
         }
     }
 }
 
+/// The rule that reduces each patient's visits to one value per phase,
+/// stated on the slide next to the numbers it produces.
+#[component]
+fn RuleSelector(rule: Aggregation, on_change: EventHandler<Aggregation>) -> Element {
+    rsx! {
+        div { class: "mb-4 flex items-center gap-3 text-xs",
+            span { class: "text-brand-taupe uppercase", "Per patient" }
+            div { class: "flex rounded-full border border-white/10 overflow-hidden",
+                for option in Aggregation::SLIDE_RULES {
+                    button {
+                        class: if option == rule { "px-3 py-1 bg-brand-orange/20 text-brand-orange font-bold cursor-pointer" } else { "px-3 py-1 text-brand-taupe hover:text-brand-light cursor-pointer" },
+                        onclick: move |_| on_change.call(option),
+                        "{option.label()}"
+                    }
+                }
+            }
+            span { class: "font-mono text-brand-taupe",
+                "{rule.describe()} · a visit is readings no more than {VISIT_GAP_HOURS} h apart, a stand test no more than {STAND_TEST_GAP_MINUTES} min"
+            }
+        }
+    }
+}
+
 #[component]
 fn TabButton(active: bool, label: String, onclick: EventHandler<MouseEvent>) -> Element {
     let base_class =
@@ -357,54 +1053,3 @@ fn TabButton(active: bool, label: String, onclick: EventHandler<MouseEvent>) ->
         }
     }
 }
-
-#[component]
-fn ChartBox(
-    title: String,
-    color: String,
-    labels: Vec<&'static str>,
-    data_a: Vec<i32>,
-    data_b: Vec<i32>,
-    legend: (&'static str, &'static str),
-    scale: f64,
-) -> Element {
-    let color_a = if color == "orange" {
-        "bg-gradient-to-t from-red-900/80 to-red-500"
-    } else {
-        "bg-gradient-to-t from-zinc-700 to-zinc-500"
-    };
-    let color_b = if color == "orange" {
-        "bg-gradient-to-t from-brand-green/80 to-emerald-400"
-    } else if color == "green" {
-        "bg-gradient-to-t from-brand-green/80 to-emerald-400"
-    } else {
-        "bg-gradient-to-t from-cyan-900/80 to-cyan-400"
-    };
-
-    rsx! {
-        div { class: "bg-zinc-900/50 rounded-2xl p-6 border border-zinc-700/50 flex flex-col",
-            h3 { class: "text-xl font-bold text-brand-light mb-6", "{title}" }
-            div { class: "flex-1 relative flex items-end justify-around px-4 pb-8 min-h-[250px]",
-                {labels.iter().enumerate().map(|(i, label)| rsx! {
-                    div { class: "relative group flex gap-2 items-end h-full w-full justify-center mx-2",
-                        if data_a[i] > 0 {
-                            div { class: "w-12 {color_a} rounded-t-sm relative hover:brightness-110 transition-all", style: "height: {(data_a[i] as f64 / scale) * 100.0}%",
-                                div { class: "absolute -top-6 left-1/2 -translate-x-1/2 text-xs font-bold text-zinc-400 opacity-0 group-hover:opacity-100", "{data_a[i]}" }
-                            }
-                        }
-                        if data_b[i] > 0 {
-                            div { class: "w-12 {color_b} rounded-t-sm relative hover:brightness-110 transition-all", style: "height: {(data_b[i] as f64 / scale) * 100.0}%",
-                                div { class: "absolute -top-6 left-1/2 -translate-x-1/2 text-xs font-bold text-brand-green opacity-0 group-hover:opacity-100", "{data_b[i]}" }
-                            }
-                        }
-                        div { class: "absolute -bottom-8 text-sm text-zinc-400 font-medium", "{label}" }
-                    }
-                })}
-            }
-            div { class: "mt-4 flex justify-center gap-6 text-sm",
-                div { class: "flex items-center gap-2", div { class: "w-3 h-3 {color_a} rounded-sm" }, span { class: "text-zinc-400", "{legend.0}" } }
-                div { class: "flex items-center gap-2", div { class: "w-3 h-3 {color_b} rounded-sm" }, span { class: "text-zinc-400", "{legend.1}" } }
-             }
-        }
-    }
-}
@@ -1,31 +1,8 @@
+use crate::components::chart::{theme, BarChart, Series};
+use crate::data::positional::PatientData;
 use dioxus::prelude::*;
-use serde::Deserialize;
-
-#[derive(Deserialize, Clone, Debug)]
-#[allow(dead_code)]
-struct PatientData {
-    mrn: String,
-    pre: PhaseData,
-    post: PhaseData,
-}
-
-#[derive(Deserialize, Clone, Debug)]
-#[allow(dead_code)]
-struct PhaseData {
-    lying: Option<Vitals>,
-    sitting: Option<Vitals>,
-    standing: Option<Vitals>,
-}
 
-#[derive(Deserialize, Clone, Debug)]
-#[allow(dead_code)]
-struct Vitals {
-    sys: i32,
-    dia: i32,
-    hr: Option<i32>,
-}
-
-const DATA_JSON: &str = include_str!("../../assets/standup_data.json");
+const DATA_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/standup_data.json"));
 
 #[component]
 pub fn Standup1() -> Element {
@@ -97,7 +74,8 @@ pub fn Standup1() -> Element {
             }
         }
 
-        let calc_avg = |sum: i32, count: i32| if count > 0 { sum / count } else { 0 };
+        // No readings in a position is "n/a" on the chart, not an average of 0.
+        let calc_avg = |sum: i32, count: i32| (count > 0).then(|| f64::from(sum / count));
 
         (
             (
@@ -128,8 +106,6 @@ pub fn Standup1() -> Element {
     });
 
     let ((pre_sys_avgs, pre_hr_avgs), (post_sys_avgs, post_hr_avgs)) = avg_stats();
-    let max_sys = 180.0;
-    let max_hr = 150.0;
 
     rsx! {
         div {
@@ -167,53 +143,26 @@ pub fn Standup1() -> Element {
                 // Right Column: Data Visualization (2 Charts)
                 div { class: "w-2/3 flex flex-col gap-4 animate-fade-in-up delay-500 overflow-y-auto pr-2",
 
-                    // Systolic Chart
-                    div {
-                        h3 { class: "text-xl font-bold text-slate-200 mb-2", "Systolic BP (Mean)" }
-                        div { class: "flex items-end justify-between h-48 bg-zinc-900/30 rounded-2xl p-4 border border-zinc-800 relative",
-                             for i in 0..4 { div { class: "absolute w-full h-px bg-zinc-800/50", style: "bottom: {i * 25}%" } }
-                            for (i, label) in ["Lying", "Sitting", "Standing"].iter().enumerate() {
-                                div { class: "flex flex-col items-center gap-2 z-10 w-1/4 h-full justify-end group",
-                                    div { class: "flex gap-2 items-end justify-center w-full h-full",
-                                        div { class: "w-8 bg-rose-500/80 rounded-t-sm relative group/bar", style: "height: {(pre_sys_avgs[i] as f64 / max_sys) * 100.0}%",
-                                            div { class: "absolute -top-6 left-1/2 -translate-x-1/2 opacity-0 group-hover/bar:opacity-100 text-rose-300 text-xs", "{pre_sys_avgs[i]}" } }
-                                        div { class: "w-8 bg-emerald-500/80 rounded-t-sm relative group/bar", style: "height: {(post_sys_avgs[i] as f64 / max_sys) * 100.0}%",
-                                             div { class: "absolute -top-6 left-1/2 -translate-x-1/2 opacity-0 group-hover/bar:opacity-100 text-emerald-300 text-xs", "{post_sys_avgs[i]}" } }
-                                    }
-                                    span { class: "text-sm text-slate-400", "{label}" }
-                                }
-                            }
-                        }
-                    }
-
-                    // Heart Rate Chart
-                    div {
-                        h3 { class: "text-xl font-bold text-slate-200 mb-2", "Heart Rate (Mean)" }
-                         div { class: "flex items-end justify-between h-48 bg-zinc-900/30 rounded-2xl p-4 border border-zinc-800 relative",
-                             for i in 0..4 { div { class: "absolute w-full h-px bg-zinc-800/50", style: "bottom: {i * 25}%" } }
-                            for (i, label) in ["Lying", "Sitting", "Standing"].iter().enumerate() {
-                                div { class: "flex flex-col items-center gap-2 z-10 w-1/4 h-full justify-end group",
-                                    div { class: "flex gap-2 items-end justify-center w-full h-full",
-                                        div { class: "w-8 bg-purple-500/80 rounded-t-sm relative group/bar", style: "height: {(pre_hr_avgs[i] as f64 / max_hr) * 100.0}%",
-                                            div { class: "absolute -top-6 left-1/2 -translate-x-1/2 opacity-0 group-hover/bar:opacity-100 text-purple-300 text-xs", "{pre_hr_avgs[i]}" } }
-                                        div { class: "w-8 bg-cyan-500/80 rounded-t-sm relative group/bar", style: "height: {(post_hr_avgs[i] as f64 / max_hr) * 100.0}%",
-                                             div { class: "absolute -top-6 left-1/2 -translate-x-1/2 opacity-0 group-hover/bar:opacity-100 text-cyan-300 text-xs", "{post_hr_avgs[i]}" } }
-                                    }
-                                    span { class: "text-sm text-slate-400", "{label}" }
-                                }
-                            }
-                        }
+                    BarChart { title: "Systolic BP (Mean)", unit: "mmHg",
+                        categories: position_labels(),
+                        series: vec![
+                            Series::partial("Pre-Op", pre_sys_avgs.to_vec(), theme::PRE),
+                            Series::partial("Post-Op", post_sys_avgs.to_vec(), theme::POST),
+                        ],
                     }
-
-                    // Legend
-                    div { class: "flex justify-center gap-6 text-sm",
-                        div { class: "flex items-center gap-2", div { class: "w-3 h-3 bg-rose-500 rounded" }, span { "Pre-Op BP" } }
-                        div { class: "flex items-center gap-2", div { class: "w-3 h-3 bg-emerald-500 rounded" }, span { "Post-Op BP" } }
-                        div { class: "flex items-center gap-2", div { class: "w-3 h-3 bg-purple-500 rounded" }, span { "Pre-Op HR" } }
-                        div { class: "flex items-center gap-2", div { class: "w-3 h-3 bg-cyan-500 rounded" }, span { "Post-Op HR" } }
+                    BarChart { title: "Heart Rate (Mean)", unit: "bpm",
+                        categories: position_labels(),
+                        series: vec![
+                            Series::partial("Pre-Op", pre_hr_avgs.to_vec(), theme::PRE),
+                            Series::partial("Post-Op", post_hr_avgs.to_vec(), theme::POST),
+                        ],
                     }
                 }
             }
         }
     }
 }
+
+fn position_labels() -> Vec<String> {
+    ["Lying", "Sitting", "Standing"].map(String::from).to_vec()
+}
@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+pub const NOTES: &str = "Play the case video: patient at 6-month follow-up. Let it run without narration, then take questions.";
+
 #[component]
 pub fn CaseVideo() -> Element {
     rsx! {
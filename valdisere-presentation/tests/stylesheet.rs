@@ -0,0 +1,76 @@
+//! `assets/tailwind.css` is compiled from `input.css` and checked in, so a
+//! class added to a component does nothing until the stylesheet is rebuilt.
+//! This fails when a class used in `src/` has no rule in it.
+
+use std::fs;
+use std::path::Path;
+
+/// Classes that are not Tailwind utilities and have no rule anywhere.
+const UNSTYLED: &[&str] = &[
+    "animate-fade-in-down",
+    "animate-fade-in-left",
+    "animate-fade-in-right",
+    "animate-fade-in-up",
+];
+
+fn rust_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_files(&path, files);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// The classes set on each line with a `class:` attribute: its string
+/// literal, or both branches of a `class: if .. { ".." } else { ".." }`.
+/// Interpolated classes such as `{color}` are skipped.
+fn classes(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| line.find("class:").map(|at| line[at + 6..].trim_start()))
+        .flat_map(|value| {
+            let literals = if value.starts_with("if ") { 2 } else { 1 };
+            value.split('"').skip(1).step_by(2).take(literals)
+        })
+        .flat_map(str::split_whitespace)
+        .filter(|class| !class.contains(['{', '}']))
+        .map(String::from)
+        .collect()
+}
+
+/// The selector Tailwind writes for `class`.
+fn selector(class: &str) -> String {
+    let mut escaped = String::from(".");
+    for c in class.chars() {
+        if !(c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[test]
+fn stylesheet_covers_every_class() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let css = fs::read_to_string(root.join("assets/tailwind.css")).unwrap();
+    let mut files = Vec::new();
+    rust_files(&root.join("src"), &mut files);
+
+    let mut missing: Vec<String> = files
+        .iter()
+        .flat_map(|file| classes(&fs::read_to_string(file).unwrap()))
+        .filter(|class| !UNSTYLED.contains(&class.as_str()))
+        .filter(|class| !css.contains(&selector(class)))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    assert!(
+        missing.is_empty(),
+        "not in assets/tailwind.css, rebuild it from input.css: {}",
+        missing.join(" ")
+    );
+}